@@ -0,0 +1,161 @@
+/* CORDIC
+ * sin/cos/atan2 done on the raw turn bits. Angles are widened to 120 bits of a
+ * turn (r64 << 56) so the rounding of the table doesn't show up in r64 results.
+ * x and y are Q124 fixed point, their magnitude never gets above 1 when rotating.
+ *
+ * Error bounds, against the exact value of the angle:
+ * sin/cos:  0.5 ULP + 2^-117 absolute
+ * atan2:    0.5 of the last bit of the r64 it returns
+ * Infinities point along whichever axes they're on, the way f64::atan2 does, and
+ * NaN has no direction so it comes out 0.
+ * Against f64 sin(to_radians()) the results agree to 2 ULP below pi/4 and to
+ * 8 EPSILON absolute past that, where f64 loses more rounding the argument.
+ * tests/angle.rs checks both.
+ */
+
+const ITER: usize = 120;
+const FRAC: i32 = 124;                   // x and y are Q124
+const K: i128 = 0x9b74eda8435e5a67f5f9092bd7fd40f; // prod 1/sqrt(1+2^-2i), Q124
+
+// atan(2^-i) in units of 2^-120 turns
+const ATAN: [i128; ITER] = [
+    0x200000000000000000000000000000, 0x12e4051d9df308665688f6dae35196, 0x09fb385b5ee39e8ddf43f3ca0921e1, 0x051111d41ddd9a1b7f9255cb1f1e29,
+    0x028b0d430e589aecc0cc001229b69e, 0x0145d7e159046278569c94de82daf7, 0x00a2f61e5c28262984d6bf58b4b45a, 0x00517c5511d442aea2c306cadeaa9b,
+    0x0028be5346d0c336fc917a6eb1ec3d, 0x00145f2ebb30ab37b9341f2d438ee8, 0x000a2f980091ba7b67f43a922119c8, 0x000517cc14a80cb70788f0039766ac,
+    0x00028be60cdfec61994b7615dea652, 0x000145f306c172f246af4bf9fd2488, 0x0000a2f9836ae91158539db461f394, 0x0000517cc1b6ba7bb2f723fe09adc5,
+    0x000028be60db85fc3a56ab54e79015, 0x0000145f306dc815e946c44abb5cc8, 0x00000a2f9836e4adee26d05512fae9, 0x00000517cc1b726b5643d5f35d89d5,
+    0x0000028be60db9383707f8b2e0318d, 0x00000145f306dc9c6d00be1096fdb3, 0x000000a2f9836e4e40aff73f306132, 0x000000517cc1b727219deea674cd12,
+    0x00000028be60db9390f7b5b415fa1a, 0x000000145f306dc9c880f2a6266f7f, 0x0000000a2f9836e4e4411c4c96a60e, 0x0000000517cc1b727220a2857bc0d1,
+    0x000000028be60db9391053cea3ee22, 0x0000000145f306dc9c882a38ceb8c8, 0x00000000a2f9836e4e44152696f49b, 0x00000000517cc1b727220a94916d54,
+    0x0000000028be60db9391054a71750b, 0x00000000145f306dc9c882a53dd252, 0x000000000a2f9836e4e441529f8c22, 0x000000000517cc1b727220a94fda70,
+    0x00000000028be60db9391054a7efc4, 0x000000000145f306dc9c882a53f834, 0x0000000000a2f9836e4e441529fc24, 0x0000000000517cc1b727220a94fe13,
+    0x000000000028be60db9391054a7f0a, 0x0000000000145f306dc9c882a53f85, 0x00000000000a2f9836e4e441529fc2, 0x00000000000517cc1b727220a94fe1,
+    0x0000000000028be60db9391054a7f1, 0x00000000000145f306dc9c882a53f8, 0x000000000000a2f9836e4e441529fc, 0x000000000000517cc1b727220a94fe,
+    0x00000000000028be60db9391054a7f, 0x000000000000145f306dc9c882a540, 0x0000000000000a2f9836e4e44152a0, 0x0000000000000517cc1b727220a950,
+    0x000000000000028be60db9391054a8, 0x0000000000000145f306dc9c882a54, 0x00000000000000a2f9836e4e44152a, 0x00000000000000517cc1b727220a95,
+    0x0000000000000028be60db9391054a, 0x00000000000000145f306dc9c882a5, 0x000000000000000a2f9836e4e44153, 0x000000000000000517cc1b727220a9,
+    0x00000000000000028be60db9391055, 0x000000000000000145f306dc9c882a, 0x0000000000000000a2f9836e4e4415, 0x0000000000000000517cc1b727220b,
+    0x000000000000000028be60db939105, 0x0000000000000000145f306dc9c883, 0x00000000000000000a2f9836e4e441, 0x00000000000000000517cc1b727221,
+    0x0000000000000000028be60db93910, 0x00000000000000000145f306dc9c88, 0x000000000000000000a2f9836e4e44, 0x000000000000000000517cc1b72722,
+    0x00000000000000000028be60db9391, 0x000000000000000000145f306dc9c9, 0x0000000000000000000a2f9836e4e4, 0x0000000000000000000517cc1b7272,
+    0x000000000000000000028be60db939, 0x0000000000000000000145f306dc9d, 0x00000000000000000000a2f9836e4e, 0x00000000000000000000517cc1b727,
+    0x0000000000000000000028be60db94, 0x00000000000000000000145f306dca, 0x000000000000000000000a2f9836e5, 0x000000000000000000000517cc1b72,
+    0x00000000000000000000028be60db9, 0x000000000000000000000145f306dd, 0x0000000000000000000000a2f9836e, 0x0000000000000000000000517cc1b7,
+    0x000000000000000000000028be60dc, 0x0000000000000000000000145f306e, 0x00000000000000000000000a2f9837, 0x00000000000000000000000517cc1b,
+    0x0000000000000000000000028be60e, 0x00000000000000000000000145f307, 0x000000000000000000000000a2f983, 0x000000000000000000000000517cc2,
+    0x00000000000000000000000028be61, 0x000000000000000000000000145f30, 0x0000000000000000000000000a2f98, 0x0000000000000000000000000517cc,
+    0x000000000000000000000000028be6, 0x0000000000000000000000000145f3, 0x00000000000000000000000000a2fa, 0x00000000000000000000000000517d,
+    0x0000000000000000000000000028be, 0x00000000000000000000000000145f, 0x000000000000000000000000000a30, 0x000000000000000000000000000518,
+    0x00000000000000000000000000028c, 0x000000000000000000000000000146, 0x0000000000000000000000000000a3, 0x000000000000000000000000000051,
+    0x000000000000000000000000000029, 0x000000000000000000000000000014, 0x00000000000000000000000000000a, 0x000000000000000000000000000005,
+    0x000000000000000000000000000003, 0x000000000000000000000000000001, 0x000000000000000000000000000001, 0x000000000000000000000000000000,
+];
+
+/* rotation mode
+ * the top two bits pick the quadrant, leaving -pi/4 to pi/4 for CORDIC which
+ * converges up to ~1.74 rad.
+ * returns (cos, sin)
+ */
+pub fn sin_cos(turn: u64) -> (f64, f64) {
+    let quadrant = turn.wrapping_add(1<<61) >> 62;
+    let rem = turn.wrapping_sub(quadrant << 62) as i64; // -pi/4 to pi/4
+
+    if rem == 0 { // multiples of pi/2 come out exact
+        return match quadrant { 0 => (1.0, 0.0), 1 => (0.0, 1.0), 2 => (-1.0, 0.0), _ => (0.0, -1.0) };
+    }
+
+    let mut z = (rem as i128) << 56;
+    let mut x = K;
+    let mut y: i128 = 0;
+    for (i, a) in ATAN.iter().enumerate() {
+        let (dx, dy) = (y >> i, x >> i);
+        if z >= 0 {
+            x -= dx;
+            y += dy;
+            z -= a;
+        } else {
+            x += dx;
+            y -= dy;
+            z += a;
+        }
+    }
+
+    let (c, s) = match quadrant {
+        0 => ( x,  y),
+        1 => (-y,  x),
+        2 => (-x, -y),
+        _ => ( y, -x),
+    };
+    return (to_f64(c), to_f64(s));
+}
+
+/* vectoring mode
+ * rotates (x, y) onto the x axis and keeps track of how far it went.
+ * returns the angle in units of 2^-120 turns
+ */
+pub fn atan2(y: f64, x: f64) -> u128 {
+    if y.is_nan() || x.is_nan() { return 0 } // no direction, same as from_radians(NaN)
+    if y.is_infinite() || x.is_infinite() { // only which ones are infinite matters, like f64
+        let side = |v: f64| if v.is_infinite() {v.signum()} else {0.0_f64.copysign(v)};
+        return atan2(side(y), side(x));
+    }
+    let m = x.abs().max(y.abs());
+    if m == 0.0 { return 0 } // matches f64::atan2(0, 0)
+
+    let e = exponent(m);
+    let mut x = scaled(x, 100 - e); // largest is 2^100-2^101, growth is < 2.4
+    let mut y = scaled(y, 100 - e);
+
+    let mut z: i128 = 0;
+    if x < 0 { // rotate by pi into the right half
+        x = -x;
+        y = -y;
+        z = 1<<119;
+    }
+    for (i, a) in ATAN.iter().enumerate() {
+        let (dx, dy) = (y >> i, x >> i);
+        if y > 0 {
+            x += dx;
+            y -= dy;
+            z += a;
+        } else {
+            x -= dx;
+            y += dy;
+            z -= a;
+        }
+    }
+    return (z as u128) & ((1<<120)-1);
+}
+
+fn to_f64(num: i128) -> f64 {
+    return num as f64 * (-FRAC as f64).exp2(); // exact scaling, one rounding
+}
+
+// v = mant*2^exp
+fn decode(v: f64) -> (i128, i32) {
+    let bits = v.to_bits();
+    let exp = ((bits >> 52) & 0x7ff) as i32;
+    let mant = (bits & ((1<<52)-1)) as i128;
+    if exp == 0 {
+        return (mant, -1074); // subnormal
+    }
+    return (mant | 1<<52, exp - 1075);
+}
+// m is in 2^e to 2^(e+1)
+fn exponent(m: f64) -> i32 {
+    let (mant, exp) = decode(m);
+    return exp + 127 - mant.leading_zeros() as i32;
+}
+// v*2^shift as an integer, without going through a float multiply
+fn scaled(v: f64, shift: i32) -> i128 {
+    let (mant, exp) = decode(v.abs());
+    let s = exp + shift;
+    let mag = if s >= 0 {
+        mant << s
+    } else if s > -127 {
+        mant >> -s
+    } else {
+        0
+    };
+    return if v.is_sign_negative() {-mag} else {mag};
+}
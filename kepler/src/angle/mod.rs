@@ -11,11 +11,215 @@ use core::fmt;
 use std::ops;
 use std::f64::consts::PI;
 
-pub struct r32 {
-    angle: u32,
+mod cordic;
+
+/* Angle
+ * Shared interface so orbits can take f64 radians, r32 or r64. f64 is passed
+ * through untouched so it keeps its old behaviour, including not wrapping.
+ */
+pub trait Angle: Copy + fmt::Debug + PartialOrd + ops::Add<Output = Self> + ops::Sub<Output = Self> + ops::Neg<Output = Self> {
+    fn from_radians(num: f64) -> Self;
+    fn to_radians(self) -> f64;
+    fn wrapped(self) -> Self; // 0-2pi, fixed point always is
+
+
+    fn sin(self) -> f64;
+    fn cos(self) -> f64;
+    fn sin_cos(self) -> (f64, f64);
+    fn atan2(y: f64, x: f64) -> Self;
+}
+impl Angle for f64 {
+    fn from_radians(num: f64) -> f64 {
+        return num;
+    }
+    fn to_radians(self) -> f64 {
+        return self;
+    }
+    fn wrapped(self) -> f64 {
+        return self.rem_euclid(2.0*PI); // exact for anything already in range
+    }
+
+    fn sin(self) -> f64 {
+        return f64::sin(self);
+    }
+    fn cos(self) -> f64 {
+        return f64::cos(self);
+    }
+    fn sin_cos(self) -> (f64, f64) {
+        return f64::sin_cos(self);
+    }
+    fn atan2(y: f64, x: f64) -> f64 {
+        return f64::atan2(y, x);
+    }
+}
+
+/* r32/r64
+ * One turn mapped onto a u32/u64. Ordering and equality are done on the raw bits
+ * so angles compare within [0, 2pi). Everything wraps, which is the whole point.
+ */
+macro_rules! fixed_angle {
+    ($name:ident, $uint:ty, $int:ty, $turn:expr) => {
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name {
+            angle: $uint,
+        }
+        impl $name {
+            pub const ZERO: $name    = $name{ angle: 0 };
+            pub const HALF_PI: $name = $name{ angle: 1<<(<$uint>::BITS-2) };
+            pub const PI: $name      = $name{ angle: 1<<(<$uint>::BITS-1) };
+
+            pub fn new(num: f64) -> $name {
+                return $name::from_radians(num);
+            }
+            pub const fn from_bits(angle: $uint) -> $name {
+                return $name{ angle };
+            }
+            pub const fn to_bits(self) -> $uint {
+                return self.angle;
+            }
+
+            pub fn from_radians(num: f64) -> $name {
+                let turns = num/(2.0*PI);
+                let frac = turns - turns.floor(); // 0-1, NaN and inf end up as 0
+                return $name{ angle: (frac*$turn).round() as u128 as $uint }; // 1.0 wraps to 0
+            }
+            pub fn from_degrees(num: f64) -> $name {
+                return $name::from_radians(num.to_radians());
+            }
+
+            // 0-2pi
+            pub fn to_radians(self) -> f64 {
+                return self.angle as f64 * (2.0*PI/$turn);
+            }
+            // -pi-pi
+            pub fn to_radians_signed(self) -> f64 {
+                return self.angle as $int as f64 * (2.0*PI/$turn);
+            }
+            pub fn to_degrees(self) -> f64 {
+                return self.to_radians().to_degrees();
+            }
+        }
+
+        impl Angle for $name {
+            fn from_radians(num: f64) -> $name {
+                return $name::from_radians(num);
+            }
+            fn to_radians(self) -> f64 {
+                return $name::to_radians(self);
+            }
+            fn wrapped(self) -> $name {
+                return self;
+            }
+
+            // all of these stay in fixed point, see cordic.rs for error bounds
+            fn sin(self) -> f64 {
+                return cordic::sin_cos(self.turn()).1;
+            }
+            fn cos(self) -> f64 {
+                return cordic::sin_cos(self.turn()).0;
+            }
+            fn sin_cos(self) -> (f64, f64) {
+                let (c, s) = cordic::sin_cos(self.turn());
+                return (s, c);
+            }
+            fn atan2(y: f64, x: f64) -> $name {
+                return $name::from_turn(cordic::atan2(y, x));
+            }
+        }
+
+        impl ops::Add for $name {
+            type Output = $name;
+            fn add(self, rhs: $name) -> $name {
+                return $name{ angle: self.angle.wrapping_add(rhs.angle) };
+            }
+        }
+        impl ops::Sub for $name {
+            type Output = $name;
+            fn sub(self, rhs: $name) -> $name {
+                return $name{ angle: self.angle.wrapping_sub(rhs.angle) };
+            }
+        }
+        impl ops::Neg for $name {
+            type Output = $name;
+            fn neg(self) -> $name {
+                return $name{ angle: self.angle.wrapping_neg() };
+            }
+        }
+        impl ops::Mul<$uint> for $name {
+            type Output = $name;
+            fn mul(self, rhs: $uint) -> $name {
+                return $name{ angle: self.angle.wrapping_mul(rhs) };
+            }
+        }
+        impl ops::Mul<$int> for $name {
+            type Output = $name;
+            fn mul(self, rhs: $int) -> $name {
+                return $name{ angle: self.angle.wrapping_mul(rhs as $uint) }; // two's complement wraps the same way
+            }
+        }
+        impl ops::AddAssign for $name {
+            fn add_assign(&mut self, rhs: $name) {
+                *self = *self + rhs;
+            }
+        }
+        impl ops::SubAssign for $name {
+            fn sub_assign(&mut self, rhs: $name) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                return fmt::Display::fmt(&self.to_radians(), f); // keeps {:.3} and friends working
+            }
+        }
+    };
 }
+
+fixed_angle!(r32, u32, i32, 4294967296.0);           // 2^32, one full rotation
+fixed_angle!(r64, u64, i64, 18446744073709551616.0); // 2^64
+
+// cordic works on 64 bits in and 120 bits out
 impl r32 {
-    pub fn new(mut num: f64) -> r32 {
-        return r32{ angle: 0 };
+    fn turn(self) -> u64 {
+        return (self.angle as u64) << 32;
+    }
+    fn from_turn(z: u128) -> r32 {
+        return r32{ angle: (z.wrapping_add(1<<87) >> 88) as u32 }; // rounded, wraps
+    }
+}
+impl r64 {
+    fn turn(self) -> u64 {
+        return self.angle;
+    }
+    fn from_turn(z: u128) -> r64 {
+        return r64{ angle: (z.wrapping_add(1<<55) >> 56) as u64 };
     }
-}
\ No newline at end of file
+}
+
+// widening is exact, the extra bits are zero
+impl From<r32> for r64 {
+    fn from(num: r32) -> r64 {
+        return r64::from_bits((num.to_bits() as u64) << 32);
+    }
+}
+// narrowing only succeeds if none of the low bits would be lost
+impl TryFrom<r64> for r32 {
+    type Error = TryFromAngleError;
+    fn try_from(num: r64) -> Result<r32, TryFromAngleError> {
+        let bits = num.to_bits();
+        if bits as u32 != 0 {
+            return Err(TryFromAngleError(()));
+        }
+        return Ok(r32::from_bits((bits >> 32) as u32));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TryFromAngleError(());
+impl fmt::Display for TryFromAngleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "angle cannot be narrowed without losing precision");
+    }
+}
+impl std::error::Error for TryFromAngleError {}
@@ -0,0 +1,80 @@
+/* README
+ * Conversions between the three anomalies, for every kind of orbit:
+ * mean:      M, what grows evenly with time
+ * eccentric: E for elliptic, H for hyperbolic and D = tan(nu/2) for parabolic
+ * true:      nu, the actual angle from periapsis
+ *
+ * Mean to eccentric is Kepler's equation and goes through the same solvers pos
+ * uses, everything else is closed form. Elliptic anomalies past one revolution
+ * stay in the same revolution, so a steadily growing M gives steadily growing E
+ * and nu. Hyperbolic nu only exists inside the asymptotes, |nu| < acos(-1/e),
+ * anything outside is NaN.
+ */
+
+use std::f64::consts::PI;
+use crate::{Orbit, Pos, Vel};
+use crate::angle::Angle;
+
+impl<A: Angle> Orbit<A> {
+    pub fn eccentric_from_mean(&self, M: f64) -> f64 {
+        if self.e < 1.0 {
+            let revs = 2.0*PI*(M/(2.0*PI)).round();
+            return self.E(M - revs) + revs; // the solvers want M in [-pi, pi]
+        } else if self.e == 1.0 {
+            return Self::D(M);
+        }
+        return self.H(M).E;
+    }
+    pub fn mean_from_eccentric(&self, E: f64) -> f64 {
+        if self.e < 1.0 {
+            return E - self.e*E.sin();
+        } else if self.e == 1.0 {
+            return E + E*E*E/3.0;
+        }
+        return self.e*E.sinh() - E;
+    }
+
+    pub fn true_from_eccentric(&self, E: f64) -> f64 {
+        let e = self.e;
+        if e < 1.0 {
+            let nu = ((1.0+e).sqrt()*(E/2.0).sin()).atan2((1.0-e).sqrt()*(E/2.0).cos())*2.0;
+            return nu + 2.0*PI*((E - nu)/(2.0*PI)).round(); // same revolution as E
+        } else if e == 1.0 {
+            return 2.0*E.atan();
+        }
+        return 2.0*(((e+1.0)/(e-1.0)).sqrt()*(E/2.0).tanh()).atan();
+    }
+    pub fn eccentric_from_true(&self, nu: f64) -> f64 {
+        let e = self.e;
+        if e < 1.0 {
+            let E = ((1.0-e*e).sqrt()*nu.sin()).atan2(e + nu.cos());
+            return E + 2.0*PI*((nu - E)/(2.0*PI)).round(); // same revolution as nu
+        } else if e == 1.0 {
+            return (nu/2.0).tan();
+        }
+        return 2.0*(((e-1.0)/(e+1.0)).sqrt()*(nu/2.0).tan()).atanh();
+    }
+
+    pub fn true_from_mean(&self, M: f64) -> f64 {
+        return self.true_from_eccentric(self.eccentric_from_mean(M));
+    }
+    pub fn mean_from_true(&self, nu: f64) -> f64 {
+        return self.mean_from_eccentric(self.eccentric_from_true(nu));
+    }
+
+    // position at true anomaly nu, no solving needed
+    pub fn pos_true(&self, nu: f64) -> Pos {
+        let r = self.semi_latus_rectum()/(1.0 + self.e*nu.cos());
+        return self.orient(( r*nu.cos(), r*nu.sin(), 0.0 ));
+    }
+    pub fn vel_true(&self, nu: f64) -> Vel {
+        let k = (self.mu/self.semi_latus_rectum()).sqrt();
+        return self.orient(( -k*nu.sin(), k*(self.e + nu.cos()), 0.0 ));
+    }
+    /* when the orbit is at nu. elliptic orbits get there once a period, this is
+     * the time in the revolution nu is in, counting the one around t0 as 0.
+     */
+    pub fn time_at_true_anomaly(&self, nu: f64) -> f64 {
+        return self.t0 + self.mean_from_true(nu)/self.mean_motion();
+    }
+}
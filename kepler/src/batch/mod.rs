@@ -0,0 +1,321 @@
+/* README
+ * Positions for lots of times on one orbit (pos_many) or lots of orbits at one
+ * time (OrbitSet). Every point gets Markley's starting guess then the same fixed
+ * number of Danby steps with no early exit, and sin/cos/cbrt/round are written out
+ * below rather than called from libm, so there is nothing in the loop that stops
+ * LLVM turning it into SIMD. The orbit's solver field is ignored.
+ *
+ * Only elliptic orbits on the Classic propagator take this path, and not those
+ * within NEAR_PARABOLIC of e = 1 which go universal. Anything else is worked out
+ * one point at a time with pos_at, same as before.
+ *
+ * cargo run --release --bin bench, x86-64 baseline (SSE2, 2 lanes), per position:
+ * pos_at loop        ~240 ns
+ * pos_many           ~40 ns
+ * OrbitSet::pos_at   ~40 ns
+ */
+
+use crate::{Orbit, Pos, Vel};
+use crate::angle::Angle;
+use std::f64::consts::{PI, FRAC_2_PI};
+
+const ITER: u32 = 2;      // Danby steps after the starting guess, 1 has been enough so far
+const LANES: usize = 8;   // points per pass, enough for AVX-512
+
+impl<A: Angle> Orbit<A> {
+    // position at every time in ts, in the same order
+    pub fn pos_many(&self, ts: &[f64]) -> Vec<Pos> {
+        if !self.batchable() {
+            return ts.iter().map(|&t| self.pos_at(t)).collect();
+        }
+        let lane = Lane::new(self);
+        let mut out = Vec::with_capacity(ts.len());
+        for j in (0..ts.len()).step_by(LANES) {
+            let t = chunk(ts, j);
+            let M = t.map(|t| lane.n*(t - lane.t0));
+            let (x, y) = plane([lane.e; LANES], [lane.a; LANES], [lane.b; LANES], M);
+            for k in 0..LANES.min(ts.len() - j) {
+                out.push(lane.orient(x[k], y[k]));
+            }
+        }
+        return out;
+    }
+
+    fn batchable(&self) -> bool {
+        return self.e < 1.0 && !self.universal();
+    }
+}
+
+/* OrbitSet
+ * The same numbers as a Vec<Orbit> but one Vec per field, so a pass over every
+ * orbit reads each field contiguously. Orbits that can't be batched are kept
+ * whole and overwrite their slot afterwards, so results stay in push order.
+ */
+pub struct OrbitSet<A: Angle = f64> {
+    e: Vec<f64>,
+    a: Vec<f64>,
+    b: Vec<f64>,
+    t0: Vec<f64>,
+    n: Vec<f64>,
+    P: [Vec<f64>; 3],   // where the orbital plane's x and y axes point
+    Q: [Vec<f64>; 3],
+    scalar: Vec<(usize, Orbit<A>)>,
+}
+impl<A: Angle> OrbitSet<A> {
+    pub fn new() -> OrbitSet<A> {
+        return OrbitSet {
+            e: Vec::new(),
+            a: Vec::new(),
+            b: Vec::new(),
+            t0: Vec::new(),
+            n: Vec::new(),
+            P: [Vec::new(), Vec::new(), Vec::new()],
+            Q: [Vec::new(), Vec::new(), Vec::new()],
+            scalar: Vec::new(),
+        };
+    }
+
+    pub fn push(&mut self, orbit: Orbit<A>) {
+        // placeholder circle for the ones done separately, it just gets overwritten
+        let lane = if orbit.batchable() {
+            Lane::new(&orbit)
+        } else {
+            self.scalar.push((self.len(), orbit));
+            Lane{ e: 0.0, a: 0.0, b: 0.0, t0: 0.0, n: 0.0, P: (0.0, 0.0, 0.0), Q: (0.0, 0.0, 0.0) }
+        };
+        self.e.push(lane.e);
+        self.a.push(lane.a);
+        self.b.push(lane.b);
+        self.t0.push(lane.t0);
+        self.n.push(lane.n);
+        for (axis, value) in self.P.iter_mut().zip([lane.P.0, lane.P.1, lane.P.2]) { axis.push(value) }
+        for (axis, value) in self.Q.iter_mut().zip([lane.Q.0, lane.Q.1, lane.Q.2]) { axis.push(value) }
+    }
+
+    pub fn len(&self) -> usize {
+        return self.e.len();
+    }
+    pub fn is_empty(&self) -> bool {
+        return self.e.is_empty();
+    }
+
+    // position of every orbit at time t, in push order
+    pub fn pos_at(&self, t: f64) -> Vec<Pos> {
+        let mut out = vec![(0.0, 0.0, 0.0); self.len()];
+        self.pos_at_into(t, &mut out);
+        return out;
+    }
+    // same, into a buffer that can be reused between steps
+    pub fn pos_at_into(&self, t: f64, out: &mut [Pos]) {
+        assert_eq!(out.len(), self.len());
+        for j in (0..self.len()).step_by(LANES) {
+            let (t0, n) = (chunk(&self.t0, j), chunk(&self.n, j));
+            let M = std::array::from_fn(|k| n[k]*(t - t0[k]));
+            let (x, y) = plane(chunk(&self.e, j), chunk(&self.a, j), chunk(&self.b, j), M);
+            let P = [chunk(&self.P[0], j), chunk(&self.P[1], j), chunk(&self.P[2], j)];
+            let Q = [chunk(&self.Q[0], j), chunk(&self.Q[1], j), chunk(&self.Q[2], j)];
+            for (k, pos) in out[j..].iter_mut().take(LANES).enumerate() {
+                *pos = (
+                    x[k]*P[0][k] + y[k]*Q[0][k],
+                    x[k]*P[1][k] + y[k]*Q[1][k],
+                    x[k]*P[2][k] + y[k]*Q[2][k],
+                );
+            }
+        }
+        for (j, orbit) in &self.scalar {
+            out[*j] = orbit.pos_at(t);
+        }
+    }
+
+    // position and velocity from the same E, the positions are pos_at's bit for bit
+    pub fn state_at(&self, t: f64) -> Vec<(Pos, Vel)> {
+        let mut out = vec![((0.0, 0.0, 0.0), (0.0, 0.0, 0.0)); self.len()];
+        self.state_at_into(t, &mut out);
+        return out;
+    }
+    pub fn state_at_into(&self, t: f64, out: &mut [(Pos, Vel)]) {
+        assert_eq!(out.len(), self.len());
+        for j in (0..self.len()).step_by(LANES) {
+            let (t0, n) = (chunk(&self.t0, j), chunk(&self.n, j));
+            let M = std::array::from_fn(|k| n[k]*(t - t0[k]));
+            let (x, y, vx, vy) = plane_state(chunk(&self.e, j), chunk(&self.a, j), chunk(&self.b, j), n, M);
+            let P = [chunk(&self.P[0], j), chunk(&self.P[1], j), chunk(&self.P[2], j)];
+            let Q = [chunk(&self.Q[0], j), chunk(&self.Q[1], j), chunk(&self.Q[2], j)];
+            for (k, state) in out[j..].iter_mut().take(LANES).enumerate() {
+                *state = ((
+                    x[k]*P[0][k] + y[k]*Q[0][k],
+                    x[k]*P[1][k] + y[k]*Q[1][k],
+                    x[k]*P[2][k] + y[k]*Q[2][k],
+                ), (
+                    vx[k]*P[0][k] + vy[k]*Q[0][k],
+                    vx[k]*P[1][k] + vy[k]*Q[1][k],
+                    vx[k]*P[2][k] + vy[k]*Q[2][k],
+                ));
+            }
+        }
+        for (j, orbit) in &self.scalar {
+            out[*j] = orbit.state_at(t);
+        }
+    }
+}
+impl<A: Angle> Default for OrbitSet<A> {
+    fn default() -> OrbitSet<A> {
+        return OrbitSet::new();
+    }
+}
+impl<A: Angle> FromIterator<Orbit<A>> for OrbitSet<A> {
+    fn from_iter<I: IntoIterator<Item = Orbit<A>>>(orbits: I) -> OrbitSet<A> {
+        let mut set = OrbitSet::new();
+        for orbit in orbits {
+            set.push(orbit);
+        }
+        return set;
+    }
+}
+
+// everything one elliptic orbit needs, orientation folded into P and Q
+struct Lane {
+    e: f64,
+    a: f64,
+    b: f64,
+    t0: f64,
+    n: f64,
+    P: Pos,
+    Q: Pos,
+}
+impl Lane {
+    fn new<A: Angle>(orbit: &Orbit<A>) -> Lane {
+        return Lane {
+            e: orbit.e,
+            a: orbit.a,
+            b: orbit.b,
+            t0: orbit.t0,
+            n: orbit.mean_motion(),
+            P: orbit.orient((1.0, 0.0, 0.0)),
+            Q: orbit.orient((0.0, 1.0, 0.0)),
+        };
+    }
+    fn orient(&self, x: f64, y: f64) -> Pos {
+        return (
+            x*self.P.0 + y*self.Q.0,
+            x*self.P.1 + y*self.Q.1,
+            x*self.P.2 + y*self.Q.2,
+        );
+    }
+}
+
+// LANES values from j on, padded with 0 past the end
+fn chunk(values: &[f64], j: usize) -> [f64; LANES] {
+    let mut out = [0.0; LANES];
+    let end = values.len().min(j + LANES);
+    out[..end-j].copy_from_slice(&values[j..end]);
+    return out;
+}
+
+/* x and y in the orbital plane, straight from M
+ * Works on LANES points side by side. Each step is a loop over the lanes with
+ * nothing depending on the others, which is the loop that gets vectorised.
+ */
+fn plane(e: [f64; LANES], a: [f64; LANES], b: [f64; LANES], M: [f64; LANES]) -> ([f64; LANES], [f64; LANES]) {
+    let E = eccentric(e, M);
+    let mut x = [0.0; LANES];
+    let mut y = [0.0; LANES];
+    for k in 0..LANES {
+        let (s, c) = sin_cos(E[k]);
+        x[k] = a[k]*(c - e[k]);
+        y[k] = b[k]*s;
+    }
+    return (x, y);
+}
+// same x and y, and the velocity from dE/dt = n/(1 - e*cos(E))
+fn plane_state(e: [f64; LANES], a: [f64; LANES], b: [f64; LANES], n: [f64; LANES], M: [f64; LANES])
+    -> ([f64; LANES], [f64; LANES], [f64; LANES], [f64; LANES]) {
+    let E = eccentric(e, M);
+    let (mut x, mut y, mut vx, mut vy) = ([0.0; LANES], [0.0; LANES], [0.0; LANES], [0.0; LANES]);
+    for k in 0..LANES {
+        let (s, c) = sin_cos(E[k]);
+        x[k] = a[k]*(c - e[k]);
+        y[k] = b[k]*s;
+        let E_dot = n[k]/(1.0 - e[k]*c);
+        vx[k] = -a[k]*s*E_dot;
+        vy[k] = b[k]*c*E_dot;
+    }
+    return (x, y, vx, vy);
+}
+fn eccentric(e: [f64; LANES], M: [f64; LANES]) -> [f64; LANES] {
+    let M = M.map(|M| M - 2.0*PI*round(M/(2.0*PI)));       // -pi-pi
+    let mut E: [f64; LANES] = std::array::from_fn(|k| start(e[k], M[k]));
+
+    for _ in 0..ITER {
+        for k in 0..LANES {
+            let (s, c) = sin_cos(E[k]);
+            let f = E[k] - e[k]*s - M[k];
+            let f1 = 1.0 - e[k]*c;
+            let f2 = e[k]*s;
+            let f3 = e[k]*c;
+            let d1 = -f/f1;
+            let d2 = -f/(f1 + d1*f2/2.0);
+            let d3 = -f/(f1 + d2*f2/2.0 + d2*d2*f3/6.0);
+            E[k] += d3;
+        }
+    }
+    return E;
+}
+
+/* Markley's starting cubic, see solver::Markley
+ * Close enough everywhere, including e near 1 with M near 0, that ITER steps of
+ * Danby always finish it. Danby's own M + 0.85*e guess can take dozens there.
+ */
+fn start(e: f64, M: f64) -> f64 {
+    const PI2: f64 = PI*PI;
+    let sign = M;
+    let M = M.abs(); // 0-pi
+    let alpha = (3.0*PI2 + 1.6*PI*(PI - M)/(1.0 + e))/(PI2 - 6.0);
+    let d = 3.0*(1.0 - e) + alpha*e;
+    let q = 2.0*alpha*d*(1.0 - e) - M*M;
+    let r = 3.0*alpha*d*(d - 1.0 + e)*M + M*M*M;
+    let w = cbrt(r.abs() + (q*q*q + r*r).sqrt());
+    let w = w*w;
+    return ((2.0*r*w/(w*w + w*q + q*q) + M)/d).copysign(sign);
+}
+
+/* round/sin/cos/cbrt without libm
+ * Adding 1.5*2^52 pushes the fraction off the end of the mantissa, which rounds
+ * to the nearest integer and leaves it in the low bits. sin/cos reduce to
+ * |r| <= pi/4 around the nearest quarter turn (pi/2 in two parts so k*PIO2_HI is
+ * exact), then the quadrant swaps and negates. Taylor series to r^16, the first
+ * term left out is below 1e-16 there. cbrt divides the exponent by 3 by dividing
+ * the bits, then Halley's method (x > 0).
+ */
+const ROUND: f64 = 6755399441055744.0; // 1.5*2^52
+const PIO2_HI: f64 = f64::from_bits(0x3FF921FB54400000); // first 33 bits of pi/2
+const PIO2_LO: f64 = f64::from_bits(0x3DD0B4611A626331); // pi/2 - PIO2_HI
+
+fn round(x: f64) -> f64 {
+    return (x + ROUND) - ROUND; // |x| < 2^51
+}
+fn cbrt(x: f64) -> f64 {
+    let mut y = f64::from_bits(x.to_bits()/3 + 0x2A9F7893782DA1CE); // within a few %
+    for _ in 0..3 {
+        let y3 = y*y*y;
+        y *= (y3 + 2.0*x)/(2.0*y3 + x);
+    }
+    return y;
+}
+fn sin_cos(x: f64) -> (f64, f64) {
+    let k = x*FRAC_2_PI + ROUND;
+    let quadrant = k.to_bits() & 3;
+    let k = k - ROUND;
+    let r = (x - k*PIO2_HI) - k*PIO2_LO;
+
+    let r2 = r*r;
+    let s = r + r*r2*(-1.0/6.0 + r2*(1.0/120.0 + r2*(-1.0/5040.0 + r2*(1.0/362880.0
+        + r2*(-1.0/39916800.0 + r2*(1.0/6227020800.0 + r2*(-1.0/1307674368000.0)))))));
+    let c = 1.0 - r2/2.0 + r2*r2*(1.0/24.0 + r2*(-1.0/720.0 + r2*(1.0/40320.0 + r2*(-1.0/3628800.0
+        + r2*(1.0/479001600.0 + r2*(-1.0/87178291200.0 + r2*(1.0/20922789888000.0)))))));
+
+    let (s, c) = if quadrant & 1 == 1 {(c, s)} else {(s, c)};
+    let s = f64::from_bits(s.to_bits() ^ ((quadrant & 2) << 62));       // quadrants 2 and 3
+    let c = f64::from_bits(c.to_bits() ^ (((quadrant + 1) & 2) << 62)); // quadrants 1 and 2
+    return (s, c);
+}
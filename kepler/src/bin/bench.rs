@@ -0,0 +1,88 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+/* Kepler solver benchmark
+ * Runs every solver over the same (e, M) grid and reports steps, time and error.
+ * The reference E is found by bisection, which can't fail to converge.
+ * After that, positions one at a time against the batch versions.
+ *
+ * cargo run --release --bin bench
+ */
+
+use std::{f64::consts::PI, hint::black_box, time::Instant};
+use kepler::{Orbit, Stat};
+use kepler::batch::OrbitSet;
+use kepler::solver::{Solver, KeplerSolver};
+use kepler::solver::table::KeplerTable;
+
+const E_GRID: [f64; 13] = [0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 0.99, 0.999, 0.999999];
+const M_COUNT: u32 = 2000;
+
+fn main() {
+    let grid: Vec<(f64, f64)> = E_GRID.iter()
+        .flat_map(|&e| (0..M_COUNT).map(move |j| (e, -PI + 2.0*PI*(j as f64 + 0.5)/M_COUNT as f64)))
+        .collect();
+    let reference: Vec<f64> = grid.iter().map(|&(e, M)| bisect(e, M)).collect();
+    let _tables: Vec<_> = E_GRID.iter().map(|&e| KeplerTable::shared(e)).collect(); // or Table is just Danby
+
+    println!("{:<10} {:>10} {:>10} {:>12} {:>10}", "solver", "avg steps", "max steps", "max error", "ns/solve");
+    for solver in Solver::ALL {
+        let mut steps = Stat::new();
+        let mut error = Stat::new();
+        for (&(e, M), &E) in grid.iter().zip(&reference) {
+            let solution = solver.solve(e, M);
+            steps.entry(solution.iter as f64, None);
+            error.entry((solution.E - E).abs(), None);
+        }
+
+        let start = Instant::now();
+        for &(e, M) in &grid {
+            black_box(solver.solve(black_box(e), black_box(M)));
+        }
+        let ns = start.elapsed().as_nanos() as f64/grid.len() as f64;
+
+        println!("{:<10} {:>10.3} {:>10} {:>12.3e} {:>10.1}", format!("{:?}", solver), steps.mean(), steps.max, error.max, ns);
+    }
+
+    println!();
+    batch();
+}
+
+// the same positions through pos_at, pos_many and OrbitSet
+fn batch() {
+    const COUNT: usize = 1<<16;
+    let orbits: Vec<Orbit> = (0..COUNT)
+        .map(|j| Orbit::new(0.9*j as f64/COUNT as f64, 7.0e6 + j as f64, 0.3, 1.0, 2.0, j as f64, 3.986e14))
+        .collect();
+    let orbit = orbits[COUNT/2];
+    let ts: Vec<f64> = (0..COUNT).map(|j| 10.0*j as f64).collect();
+
+    let ns = |run: &dyn Fn()| {
+        run(); // warm up
+        let start = Instant::now();
+        run();
+        return start.elapsed().as_nanos() as f64/COUNT as f64;
+    };
+    let scalar_times = ns(&|| for &t in &ts { black_box(orbit.pos_at(black_box(t))); });
+    let many = ns(&|| { black_box(orbit.pos_many(black_box(&ts))); });
+    let scalar_orbits = ns(&|| for orbit in &orbits { black_box(orbit.pos_at(black_box(1000.0))); });
+    let set: OrbitSet = orbits.iter().copied().collect();
+    let together = ns(&|| { black_box(set.pos_at(black_box(1000.0))); });
+
+    println!("{:<22} {:>10}", "positions", "ns/pos");
+    println!("{:<22} {:>10.1}", "pos_at, many times", scalar_times);
+    println!("{:<22} {:>10.1}", "pos_many", many);
+    println!("{:<22} {:>10.1}", "pos_at, many orbits", scalar_orbits);
+    println!("{:<22} {:>10.1}", "OrbitSet::pos_at", together);
+}
+
+// E - e*sin(E) is increasing and E is within e of M
+fn bisect(e: f64, M: f64) -> f64 {
+    let (mut lo, mut hi) = (M - e - 1e-9, M + e + 1e-9);
+    while hi - lo > 0.0 {
+        let mid = 0.5*(lo + hi);
+        if mid == lo || mid == hi { break }
+        if mid - e*mid.sin() < M { lo = mid } else { hi = mid }
+    }
+    return 0.5*(lo + hi);
+}
@@ -0,0 +1,121 @@
+/* README
+ * Bodies orbiting bodies, the patched conics picture. A System is a tree with a
+ * single root (the star) and every other body on an Orbit around its parent, so
+ * the orbit's mu has to be the parent's, add checks that. Absolute positions walk up the tree and
+ * add each orbit's offset in fixed point, so a moon's position is as precise at
+ * the edge of the system as near the star.
+ *
+ * Inside a body's sphere of influence its gravity is the only one that counts.
+ * The SOI is Laplace's a*(mu/mu_parent)^(2/5) unless it's set by hand.
+ */
+
+use core::fmt;
+use std::ops::Index;
+use crate::{Orbit, Vel};
+use crate::angle::Angle;
+use crate::position::{Position, Displacement};
+use crate::vector::add;
+
+// index into a System, only means something for the System that made it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BodyId(usize);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Body<A: Angle = f64> {
+    pub name: String,
+    pub mu: f64,        // gravitational parameter, G*mass
+    pub radius: f64,
+    pub soi: f64,       // sphere of influence radius, infinite for the root
+    pub orbit: Option<Orbit<A>>, // around the parent, None for the root
+    pub parent: Option<BodyId>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SystemError {
+    Mu,     // the orbit's mu isn't the parent's
+}
+impl fmt::Display for SystemError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            SystemError::Mu => write!(f, "orbit's gravitational parameter is not the parent body's"),
+        };
+    }
+}
+impl std::error::Error for SystemError {}
+
+pub struct System<A: Angle = f64> {
+    bodies: Vec<Body<A>>,
+}
+impl<A: Angle> System<A> {
+    // a system with just the root in it, sitting at the origin
+    pub fn new(name: &str, mu: f64, radius: f64) -> System<A> {
+        let root = Body{ name: name.to_string(), mu, radius, soi: f64::INFINITY, orbit: None, parent: None };
+        return System{ bodies: vec![root] };
+    }
+    pub fn root(&self) -> BodyId {
+        return BodyId(0);
+    }
+
+    // adds a body orbiting parent, with the Laplace SOI
+    pub fn add(&mut self, parent: BodyId, name: &str, mu: f64, radius: f64, orbit: Orbit<A>) -> Result<BodyId, SystemError> {
+        let soi = Self::laplace_soi(&orbit, mu, self[parent].mu);
+        return self.add_with_soi(parent, name, mu, radius, soi, orbit);
+    }
+    pub fn add_with_soi(&mut self, parent: BodyId, name: &str, mu: f64, radius: f64, soi: f64, orbit: Orbit<A>) -> Result<BodyId, SystemError> {
+        if orbit.mu != self[parent].mu {
+            return Err(SystemError::Mu);
+        }
+        self.bodies.push(Body{ name: name.to_string(), mu, radius, soi, orbit: Some(orbit), parent: Some(parent) });
+        return Ok(BodyId(self.bodies.len() - 1));
+    }
+    pub fn laplace_soi(orbit: &Orbit<A>, mu: f64, parent_mu: f64) -> f64 {
+        return orbit.a.abs()*(mu/parent_mu).powf(0.4);
+    }
+
+    pub fn len(&self) -> usize {
+        return self.bodies.len();
+    }
+    pub fn is_empty(&self) -> bool {
+        return self.bodies.is_empty();
+    }
+    pub fn ids(&self) -> impl Iterator<Item = BodyId> {
+        return (0..self.bodies.len()).map(BodyId);
+    }
+    pub fn find(&self, name: &str) -> Option<BodyId> {
+        return self.ids().find(|&id| self[id].name == name);
+    }
+    pub fn children(&self, id: BodyId) -> impl Iterator<Item = BodyId> + '_ {
+        return self.ids().filter(move |&child| self[child].parent == Some(id));
+    }
+    // id, its parent, its parent's parent... up to the root
+    pub fn ancestors(&self, id: BodyId) -> impl Iterator<Item = BodyId> + '_ {
+        return std::iter::successors(Some(id), |&id| self[id].parent);
+    }
+
+    // absolute position at time t, every orbit up to the root added together
+    pub fn pos_at(&self, id: BodyId, t: f64) -> Position {
+        let mut pos = Position::ORIGIN;
+        for id in self.ancestors(id) {
+            if let Some(orbit) = &self[id].orbit {
+                pos += orbit.pos_fixed_at(t);
+            }
+        }
+        return pos;
+    }
+    // velocity relative to the root
+    pub fn vel_at(&self, id: BodyId, t: f64) -> Vel {
+        return self.ancestors(id)
+            .filter_map(|id| self[id].orbit.as_ref())
+            .fold((0.0, 0.0, 0.0), |vel, orbit| add(vel, orbit.vel_at(t)));
+    }
+    // from one body to another
+    pub fn displacement(&self, from: BodyId, to: BodyId, t: f64) -> Displacement {
+        return self.pos_at(to, t) - self.pos_at(from, t);
+    }
+}
+impl<A: Angle> Index<BodyId> for System<A> {
+    type Output = Body<A>;
+    fn index(&self, id: BodyId) -> &Body<A> {
+        return &self.bodies[id.0];
+    }
+}
@@ -0,0 +1,228 @@
+/* README
+ * Building an orbit out of whichever numbers are at hand, checked on the way.
+ * Orbit::new takes anything and makes something of it, this says what's wrong
+ * instead. The size is one of:
+ * periapsis:       with e, which works for every kind of orbit
+ * semi_major_axis: with e, a < 0 for hyperbolic so it's the same a as Orbit has
+ * apsides:         periapsis and apoapsis, e follows from them
+ * period:          with e and mu, elliptic only
+ * and the timing is t0 or a mean anomaly at some epoch. e defaults to 0, the
+ * angles and t0 to 0.
+ *
+ * Angles come out normalised: o and w to 0-2pi and i to 0-pi. An inclination
+ * past pi is the same orbit going the other way round, tilted by 2pi - i with
+ * o and w turned half way round. r32/r64 angles wrap by themselves, so they come
+ * out bit for bit unless i gets flipped.
+ */
+
+use core::fmt;
+use std::f64::consts::PI;
+use crate::{Orbit, Propagator};
+use crate::angle::Angle;
+use crate::solver::Solver;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OrbitError {
+    NotFinite,      // NaN or infinite
+    NoSize,         // none of periapsis, a, apsides or period
+    Overdetermined, // more than one size, or both t0 and a mean anomaly
+    Mu,             // not positive
+    Eccentricity,   // negative, or not the kind of orbit the size is for
+    Size,           // not positive, or apoapsis inside periapsis
+}
+impl fmt::Display for OrbitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            OrbitError::NotFinite => write!(f, "orbit parameters have to be finite"),
+            OrbitError::NoSize => write!(f, "orbit needs a periapsis, semi-major axis, apsides or period"),
+            OrbitError::Overdetermined => write!(f, "orbit was given more than one size or epoch"),
+            OrbitError::Mu => write!(f, "gravitational parameter has to be positive"),
+            OrbitError::Eccentricity => write!(f, "eccentricity is negative or doesn't fit the size"),
+            OrbitError::Size => write!(f, "orbit size is not positive or apoapsis is below periapsis"),
+        };
+    }
+}
+impl std::error::Error for OrbitError {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Size {
+    Periapsis(f64),
+    SemiMajorAxis(f64),
+    Apsides(f64, f64),
+    Period(f64),
+}
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Epoch {
+    T0(f64),
+    Mean{ M: f64, t: f64 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrbitBuilder<A: Angle = f64> {
+    mu: f64,
+    e: Option<f64>,
+    size: Option<Size>,
+    epoch: Option<Epoch>,
+    i: A,
+    o: A,
+    w: A,
+    propagator: Propagator,
+    solver: Solver,
+    overdetermined: bool,
+}
+
+impl<A: Angle> Orbit<A> {
+    pub fn builder(mu: f64) -> OrbitBuilder<A> {
+        return OrbitBuilder::new(mu);
+    }
+}
+
+impl<A: Angle> OrbitBuilder<A> {
+    pub fn new(mu: f64) -> OrbitBuilder<A> {
+        let zero = A::from_radians(0.0);
+        return OrbitBuilder {
+            mu,
+            e: None,
+            size: None,
+            epoch: None,
+            i: zero,
+            o: zero,
+            w: zero,
+            propagator: Propagator::Classic,
+            solver: Solver::Newton,
+            overdetermined: false,
+        };
+    }
+
+    pub fn e(mut self, e: f64) -> Self {
+        self.e = Some(e);
+        return self;
+    }
+    pub fn periapsis(self, q: f64) -> Self {
+        return self.size(Size::Periapsis(q));
+    }
+    pub fn semi_major_axis(self, a: f64) -> Self {
+        return self.size(Size::SemiMajorAxis(a));
+    }
+    pub fn apsides(self, periapsis: f64, apoapsis: f64) -> Self {
+        return self.size(Size::Apsides(periapsis, apoapsis));
+    }
+    pub fn period(self, period: f64) -> Self {
+        return self.size(Size::Period(period));
+    }
+
+    pub fn i(mut self, i: A) -> Self {
+        self.i = i;
+        return self;
+    }
+    pub fn o(mut self, o: A) -> Self {
+        self.o = o;
+        return self;
+    }
+    pub fn w(mut self, w: A) -> Self {
+        self.w = w;
+        return self;
+    }
+
+    pub fn t0(self, t0: f64) -> Self {
+        return self.epoch(Epoch::T0(t0));
+    }
+    // M in radians at time t, f64 so hyperbolic orbits can go past 2pi
+    pub fn mean_anomaly(self, M: f64, t: f64) -> Self {
+        return self.epoch(Epoch::Mean{ M, t });
+    }
+
+    pub fn propagator(mut self, propagator: Propagator) -> Self {
+        self.propagator = propagator;
+        return self;
+    }
+    pub fn solver(mut self, solver: Solver) -> Self {
+        self.solver = solver;
+        return self;
+    }
+
+    pub fn build(&self) -> Result<Orbit<A>, OrbitError> {
+        if self.overdetermined {
+            return Err(OrbitError::Overdetermined);
+        }
+        let size = self.size.ok_or(OrbitError::NoSize)?;
+        let numbers = match size {
+            Size::Periapsis(x) | Size::SemiMajorAxis(x) | Size::Period(x) => [x, 0.0],
+            Size::Apsides(q, Q) => [q, Q],
+        };
+        let angles = [self.i, self.o, self.w].map(|angle| angle.to_radians());
+        let epoch = match self.epoch {
+            Some(Epoch::T0(t0)) => [t0, 0.0],
+            Some(Epoch::Mean{ M, t }) => [M, t],
+            None => [0.0, 0.0],
+        };
+        let e = self.e.unwrap_or(0.0);
+        if [self.mu, e].iter().chain(&numbers).chain(&angles).chain(&epoch).any(|x| !x.is_finite()) {
+            return Err(OrbitError::NotFinite);
+        }
+        if self.mu <= 0.0 {
+            return Err(OrbitError::Mu);
+        }
+        if e < 0.0 {
+            return Err(OrbitError::Eccentricity);
+        }
+
+        let (e, q) = match size {
+            Size::Periapsis(q) => (e, q),
+            Size::SemiMajorAxis(a) => {
+                if a == 0.0 { return Err(OrbitError::Size) }
+                if e == 1.0 || (a > 0.0) != (e < 1.0) { return Err(OrbitError::Eccentricity) }
+                (e, a*(1.0 - e))
+            },
+            Size::Apsides(q, Q) => {
+                if self.e.is_some() { return Err(OrbitError::Overdetermined) }
+                if Q < q { return Err(OrbitError::Size) }
+                ((Q - q)/(Q + q), q)
+            },
+            Size::Period(T) => {
+                if T <= 0.0 { return Err(OrbitError::Size) }
+                if e >= 1.0 { return Err(OrbitError::Eccentricity) }
+                let a = (self.mu*T*T/(4.0*PI*PI)).cbrt();
+                (e, a*(1.0 - e))
+            },
+        };
+        if q <= 0.0 {
+            return Err(OrbitError::Size);
+        }
+
+        let (i, o, w) = normalise(self.i, self.o, self.w);
+        let mut orbit = Orbit::new(e, q, i, o, w, 0.0, self.mu);
+        orbit.t0 = match self.epoch {
+            Some(Epoch::T0(t0)) => t0,
+            Some(Epoch::Mean{ M, t }) => t - M/orbit.mean_motion(),
+            None => 0.0,
+        };
+        orbit.propagator = self.propagator;
+        orbit.solver = self.solver;
+        return Ok(orbit);
+    }
+
+    fn size(mut self, size: Size) -> Self {
+        self.overdetermined |= self.size.is_some();
+        self.size = Some(size);
+        return self;
+    }
+    fn epoch(mut self, epoch: Epoch) -> Self {
+        self.overdetermined |= self.epoch.is_some();
+        self.epoch = Some(epoch);
+        return self;
+    }
+}
+
+/* i to 0-pi, o and w to 0-2pi, without moving the orbit. Done in the angle type
+ * so r32/r64 keep every bit, for them only the flip does anything.
+ */
+fn normalise<A: Angle>(i: A, o: A, w: A) -> (A, A, A) {
+    let pi = A::from_radians(PI);
+    let (i, o, w) = (i.wrapped(), o.wrapped(), w.wrapped());
+    if i > pi {
+        // the other way round: Rz(pi) Rx(-i) Rz(pi) = Rx(i)
+        return ((-i).wrapped(), (o + pi).wrapped(), (w + pi).wrapped());
+    }
+    return (i, o, w);
+}
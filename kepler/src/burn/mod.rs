@@ -0,0 +1,42 @@
+/* README
+ * Impulsive burns. The velocity changes instantly at t and the position doesn't,
+ * so the new orbit is whatever from_state makes of the state afterwards. Nothing
+ * here cares which side of e = 1 either orbit is on, see state for how parabolic
+ * and circular results come out.
+ *
+ * The local frame follows the velocity:
+ * prograde: along the velocity
+ * normal:   along the angular momentum, r x v
+ * radial:   prograde x normal, in the orbital plane and away from the focus
+ */
+
+use crate::{Orbit, Vel};
+use crate::angle::Angle;
+use crate::vector::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DeltaV {
+    Inertial(Vel),                                          // same axes as pos/vel
+    Local{ prograde: f64, normal: f64, radial: f64 },       // see above
+}
+
+impl<A: Angle> Orbit<A> {
+    // the orbit after a burn of dv at time t, keeping the propagator and solver
+    pub fn apply_burn(&self, t: f64, dv: DeltaV) -> Orbit<A> {
+        let (pos, vel) = self.state_at(t);
+        let dv = match dv {
+            DeltaV::Inertial(dv) => dv,
+            DeltaV::Local{ prograde, normal, radial } => {
+                let prograde_hat = unit(vel);
+                let normal_hat = unit(cross(pos, vel));
+                let radial_hat = cross(prograde_hat, normal_hat);
+                add(add(scale(prograde_hat, prograde), scale(normal_hat, normal)), scale(radial_hat, radial))
+            }
+        };
+
+        let mut orbit = Orbit::from_state(pos, add(vel, dv), self.mu, t);
+        orbit.propagator = self.propagator;
+        orbit.solver = self.solver;
+        return orbit;
+    }
+}
@@ -0,0 +1,85 @@
+/* README
+ * A big list of orbits propagated on every core, needs the parallel feature.
+ * Orbits are split into OrbitSets of CHUNK in push order and each chunk is one
+ * job. The split doesn't depend on how many threads there are and no job reads
+ * another's results, so every position comes out bit for bit the same whatever
+ * the thread count, in push order. Elliptic orbits go through the batch loop,
+ * which ignores each orbit's solver (see batch), for velocities too, so pos_at
+ * and state_at give the same positions. They can differ from Orbit::pos_at in
+ * the last bits.
+ */
+
+use rayon::prelude::*;
+use crate::{Orbit, Pos, Vel};
+use crate::angle::Angle;
+use crate::batch::OrbitSet;
+
+const CHUNK: usize = 1024; // orbits per job, fixed so results don't depend on the thread count
+
+pub struct OrbitCatalog<A: Angle = f64> {
+    orbits: Vec<Orbit<A>>,
+    sets: Vec<OrbitSet<A>>, // the same orbits CHUNK at a time
+}
+impl<A: Angle + Send + Sync> OrbitCatalog<A> {
+    pub fn new() -> OrbitCatalog<A> {
+        return OrbitCatalog { orbits: Vec::new(), sets: Vec::new() };
+    }
+
+    pub fn push(&mut self, orbit: Orbit<A>) {
+        if self.orbits.len().is_multiple_of(CHUNK) {
+            self.sets.push(OrbitSet::new());
+        }
+        self.sets.last_mut().unwrap().push(orbit);
+        self.orbits.push(orbit);
+    }
+
+    pub fn len(&self) -> usize {
+        return self.orbits.len();
+    }
+    pub fn is_empty(&self) -> bool {
+        return self.orbits.is_empty();
+    }
+    pub fn orbits(&self) -> &[Orbit<A>] {
+        return &self.orbits;
+    }
+
+    // position of every orbit at time t, in push order
+    pub fn pos_at(&self, t: f64) -> Vec<Pos> {
+        let mut out = vec![(0.0, 0.0, 0.0); self.len()];
+        self.pos_at_into(t, &mut out);
+        return out;
+    }
+    // same, into a buffer that can be reused between frames
+    pub fn pos_at_into(&self, t: f64, out: &mut [Pos]) {
+        assert_eq!(out.len(), self.len());
+        out.par_chunks_mut(CHUNK)
+            .zip(self.sets.par_iter())
+            .for_each(|(out, set)| set.pos_at_into(t, out));
+    }
+    // position and velocity of every orbit, the positions are pos_at's bit for bit
+    pub fn state_at(&self, t: f64) -> Vec<(Pos, Vel)> {
+        let mut out = vec![((0.0, 0.0, 0.0), (0.0, 0.0, 0.0)); self.len()];
+        self.state_at_into(t, &mut out);
+        return out;
+    }
+    pub fn state_at_into(&self, t: f64, out: &mut [(Pos, Vel)]) {
+        assert_eq!(out.len(), self.len());
+        out.par_chunks_mut(CHUNK)
+            .zip(self.sets.par_iter())
+            .for_each(|(out, set)| set.state_at_into(t, out));
+    }
+}
+impl<A: Angle + Send + Sync> Default for OrbitCatalog<A> {
+    fn default() -> OrbitCatalog<A> {
+        return OrbitCatalog::new();
+    }
+}
+impl<A: Angle + Send + Sync> FromIterator<Orbit<A>> for OrbitCatalog<A> {
+    fn from_iter<I: IntoIterator<Item = Orbit<A>>>(orbits: I) -> OrbitCatalog<A> {
+        let mut catalog = OrbitCatalog::new();
+        for orbit in orbits {
+            catalog.push(orbit);
+        }
+        return catalog;
+    }
+}
@@ -0,0 +1,51 @@
+/* README
+ * Quantities that follow from the elements and mu, per unit mass. They work for
+ * every kind of orbit where they mean anything, and where they don't it's None
+ * rather than a number that looks fine:
+ * p:       semi-latus rectum, the radius at nu = +-pi/2
+ * period:  elliptic only
+ * energy:  -mu/2a, 0 for parabolic and positive for hyperbolic
+ * h:       angular momentum r x v, along the orbit's normal
+ * speed:   vis-viva at a radius
+ * v_inf:   speed left over far away, hyperbolic and parabolic (0) only
+ * nu_inf:  true anomaly of the outgoing asymptote, pi for parabolic
+ *
+ * mean_motion, periapsis and apoapsis live with the elements themselves.
+ */
+
+use std::f64::consts::PI;
+use crate::Orbit;
+use crate::angle::Angle;
+use crate::vector::*;
+
+impl<A: Angle> Orbit<A> {
+    pub fn semi_latus_rectum(&self) -> f64 {
+        return self.q*(1.0 + self.e); // a(1-e^2) without the 0*inf at e = 1
+    }
+    pub fn period(&self) -> Option<f64> {
+        if self.e >= 1.0 { return None }
+        return Some(2.0*PI/self.mean_motion());
+    }
+    pub fn specific_energy(&self) -> f64 {
+        if self.e == 1.0 { return 0.0 }
+        return -self.mu/(2.0*self.a);
+    }
+    pub fn angular_momentum(&self) -> (f64, f64, f64) {
+        let h = (self.mu*self.semi_latus_rectum()).sqrt();
+        return scale(self.orient((0.0, 0.0, 1.0)), h);
+    }
+    // vis-viva, NaN for radii the orbit never reaches
+    pub fn speed_at_radius(&self, r: f64) -> f64 {
+        return (2.0*(self.specific_energy() + self.mu/r)).sqrt();
+    }
+
+    pub fn excess_speed(&self) -> Option<f64> {
+        if self.e < 1.0 { return None }
+        return Some((2.0*self.specific_energy()).sqrt());
+    }
+    // the incoming asymptote is at minus this
+    pub fn asymptote_angle(&self) -> Option<f64> {
+        if self.e < 1.0 { return None }
+        return Some((-1.0/self.e).acos());
+    }
+}
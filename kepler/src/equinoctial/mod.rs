@@ -0,0 +1,334 @@
+/* README
+ * Element sets without the classical singularities. w needs a periapsis and o
+ * needs a node, so near circular or near equatorial orbits have them jumping
+ * around even though the orbit barely changes. These only use the sums, which
+ * stay put, and the eccentricity and tilt as vectors:
+ *
+ * equinoctial (Broucke and Cefola), elliptic only:
+ * a       semi-major axis
+ * h, k    e*sin(w+o), e*cos(w+o)
+ * p, q    tan(i/2)*sin(o), tan(i/2)*cos(o)
+ * lambda  mean longitude, M + w + o
+ *
+ * modified equinoctial (Walker), every kind of conic:
+ * p       semi-latus rectum
+ * f, g    e*cos(w+o), e*sin(w+o)
+ * h, k    tan(i/2)*cos(o), tan(i/2)*sin(o)
+ * L       true longitude, nu + w + o
+ *
+ * Both are at time t. The direct set is used, so i = pi is singular now instead
+ * (the only thing that errors) and it gets less precise close to it.
+ *
+ * Positions come from the equinoctial frame, f along the node turned back by o
+ * and g 90 degrees on in the plane, so nothing goes through w or o. Propagating
+ * is Kepler's equation in the eccentric longitude F = E + w + o, which is smooth
+ * through e = 0. Like Orbit, state returns whatever F had when the iterations ran
+ * out and try_state returns a KeplerError instead. Modified ones go through the
+ * equinoctial ones when elliptic, otherwise through Orbit where w is always defined.
+ */
+
+use core::fmt;
+use std::f64::consts::PI;
+use crate::{Orbit, Pos, Vel};
+use crate::angle::Angle;
+use crate::solver::{done, KeplerError, Solution};
+use crate::vector::*;
+
+const MAX_ITER: u32 = 50;
+const PARABOLIC: f64 = 1e-11; // same as from_state, e this close to 1 is made parabolic
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EquinoctialError {
+    NotElliptic,    // equinoctial elements need a > 0
+    Retrograde,     // i = pi, tan(i/2) is infinite
+}
+impl fmt::Display for EquinoctialError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            EquinoctialError::NotElliptic => write!(f, "equinoctial elements need an elliptic orbit"),
+            EquinoctialError::Retrograde => write!(f, "direct equinoctial elements are singular at i = pi"),
+        };
+    }
+}
+impl std::error::Error for EquinoctialError {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EquinoctialElements {
+    pub a: f64,
+    pub h: f64,
+    pub k: f64,
+    pub p: f64,
+    pub q: f64,
+    pub lambda: f64,
+    pub t: f64,
+    pub mu: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ModifiedEquinoctialElements {
+    pub p: f64,
+    pub f: f64,
+    pub g: f64,
+    pub h: f64,
+    pub k: f64,
+    pub L: f64,
+    pub t: f64,
+    pub mu: f64,
+}
+
+impl EquinoctialElements {
+    pub fn from_orbit<A: Angle>(orbit: &Orbit<A>, t: f64) -> Result<EquinoctialElements, EquinoctialError> {
+        if orbit.e >= 1.0 {
+            return Err(EquinoctialError::NotElliptic);
+        }
+        let (i, o, w) = (orbit.i.to_radians(), orbit.o.to_radians(), orbit.w.to_radians());
+        if i.cos() <= -1.0 {
+            return Err(EquinoctialError::Retrograde);
+        }
+        let tilt = (i/2.0).tan();
+        return Ok(EquinoctialElements {
+            a: orbit.a,
+            h: orbit.e*(w + o).sin(),
+            k: orbit.e*(w + o).cos(),
+            p: tilt*o.sin(),
+            q: tilt*o.cos(),
+            lambda: wrap(orbit.mean_anomaly(t) + w + o),
+            t,
+            mu: orbit.mu,
+        });
+    }
+    pub fn to_orbit<A: Angle>(&self) -> Orbit<A> {
+        let e = self.h.hypot(self.k);
+        let o = self.p.atan2(self.q);
+        let periapsis = self.h.atan2(self.k); // longitude of, w + o
+        let mut orbit = Orbit::new(
+            e,
+            self.a*(1.0 - e),
+            A::from_radians(2.0*self.p.hypot(self.q).atan()),
+            A::from_radians(o.rem_euclid(2.0*PI)),
+            A::from_radians((periapsis - o).rem_euclid(2.0*PI)),
+            0.0,
+            self.mu
+        );
+        orbit.t0 = self.t - wrap(self.lambda - periapsis)/self.mean_motion();
+        return orbit;
+    }
+
+    pub fn from_state(pos: Pos, vel: Vel, mu: f64, t: f64) -> Result<EquinoctialElements, EquinoctialError> {
+        let (r, v) = (norm(pos), norm(vel));
+        let a = 1.0/(2.0/r - v*v/mu);
+        if a <= 0.0 || !a.is_finite() {
+            return Err(EquinoctialError::NotElliptic);
+        }
+        let (p, q) = tilt(cross(pos, vel)).ok_or(EquinoctialError::Retrograde)?;
+        let (f_hat, g_hat) = frame(p, q);
+        let e_vec = scale(sub(scale(pos, v*v - mu/r), scale(vel, dot(pos, vel))), 1.0/mu);
+
+        let mut elements = EquinoctialElements{ a, h: dot(e_vec, g_hat), k: dot(e_vec, f_hat), p, q, lambda: 0.0, t, mu };
+        let F = elements.eccentric_longitude(dot(pos, f_hat), dot(pos, g_hat));
+        elements.lambda = F + elements.h*F.cos() - elements.k*F.sin();
+        return Ok(elements);
+    }
+    pub fn state(&self) -> (Pos, Vel) {
+        return self.state_from(self.F().E);
+    }
+    pub fn try_state(&self) -> Result<(Pos, Vel), KeplerError> {
+        let F = self.F();
+        if !F.converged || !F.E.is_finite() {
+            let (h, k, lambda) = (self.h, self.k, self.lambda);
+            let residual = F.E + h*F.E.cos() - k*F.E.sin() - lambda;
+            return Err(KeplerError{ iter: F.iter, residual, e: h.hypot(k), M: wrap(lambda - h.atan2(k)) });
+        }
+        return Ok(self.state_from(F.E));
+    }
+    fn state_from(&self, F: f64) -> (Pos, Vel) {
+        let (f_hat, g_hat) = frame(self.p, self.q);
+        let (x, y, vx, vy) = self.plane(F);
+        return (add(scale(f_hat, x), scale(g_hat, y)), add(scale(f_hat, vx), scale(g_hat, vy)));
+    }
+
+    // the same orbit with lambda moved on to t
+    pub fn at(&self, t: f64) -> EquinoctialElements {
+        let lambda = wrap(self.lambda + self.mean_motion()*(t - self.t));
+        return EquinoctialElements{ lambda, t, ..*self };
+    }
+    pub fn state_at(&self, t: f64) -> (Pos, Vel) {
+        return self.at(t).state();
+    }
+    pub fn try_state_at(&self, t: f64) -> Result<(Pos, Vel), KeplerError> {
+        return self.at(t).try_state();
+    }
+
+    pub fn mean_motion(&self) -> f64 {
+        return (self.mu/(self.a*self.a*self.a)).sqrt();
+    }
+
+    /* Kepler's equation in longitudes, lambda = F + h*cos(F) - k*sin(F). Newton
+     * from Danby's guess, e*sin(M) is k*sin(lambda) - h*cos(lambda) so it works
+     * out without M. Stops like the solvers do, F can be 0 away from periapsis
+     * so e is added to the scale.
+     */
+    fn F(&self) -> Solution {
+        let (h, k, lambda) = (self.h, self.k, self.lambda);
+        let e = h.hypot(k);
+        let mut F = lambda + 0.85*e*(k*lambda.sin() - h*lambda.cos()).signum();
+        for i in 0..MAX_ITER {
+            let (s, c) = F.sin_cos();
+            let f1 = 1.0 - h*s - k*c;
+            let step = (F + h*c - k*s - lambda)/f1;
+            F -= step;
+            if done(step, F.abs() + e, f1) {
+                return Solution{ E: F, iter: i+1, converged: true };
+            }
+        }
+        return Solution{ E: F, iter: MAX_ITER, converged: false };
+    }
+    // position and velocity along f and g at eccentric longitude F
+    fn plane(&self, F: f64) -> (f64, f64, f64, f64) {
+        let (a, h, k) = (self.a, self.h, self.k);
+        let beta = 1.0/(1.0 + (1.0 - h*h - k*k).sqrt());
+        let (s, c) = F.sin_cos();
+        let r = a*(1.0 - k*c - h*s);
+        let rate = self.mean_motion()*a*a/r;
+        return (
+            a*((1.0 - h*h*beta)*c + h*k*beta*s - k),
+            a*((1.0 - k*k*beta)*s + h*k*beta*c - h),
+            rate*(h*k*beta*c - (1.0 - h*h*beta)*s),
+            rate*((1.0 - k*k*beta)*c - h*k*beta*s),
+        );
+    }
+    // F from a position along f and g, the inverse of plane
+    fn eccentric_longitude(&self, x: f64, y: f64) -> f64 {
+        let (a, h, k) = (self.a, self.h, self.k);
+        let beta = 1.0/(1.0 + (1.0 - h*h - k*k).sqrt());
+        let b = a*(1.0 - h*h - k*k).sqrt();
+        let c = k + ((1.0 - k*k*beta)*x - h*k*beta*y)/b;
+        let s = h + ((1.0 - h*h*beta)*y - h*k*beta*x)/b;
+        return s.atan2(c);
+    }
+}
+
+impl ModifiedEquinoctialElements {
+    pub fn from_orbit<A: Angle>(orbit: &Orbit<A>, t: f64) -> Result<ModifiedEquinoctialElements, EquinoctialError> {
+        let (i, o, w) = (orbit.i.to_radians(), orbit.o.to_radians(), orbit.w.to_radians());
+        if i.cos() <= -1.0 {
+            return Err(EquinoctialError::Retrograde);
+        }
+        let tilt = (i/2.0).tan();
+        return Ok(ModifiedEquinoctialElements {
+            p: orbit.semi_latus_rectum(),
+            f: orbit.e*(w + o).cos(),
+            g: orbit.e*(w + o).sin(),
+            h: tilt*o.cos(),
+            k: tilt*o.sin(),
+            L: orbit.true_from_mean(orbit.mean_anomaly(t)) + w + o,
+            t,
+            mu: orbit.mu,
+        });
+    }
+    pub fn to_orbit<A: Angle>(&self) -> Orbit<A> {
+        let mut e = self.f.hypot(self.g);
+        if (e - 1.0).abs() < PARABOLIC {
+            e = 1.0;
+        }
+        let o = self.k.atan2(self.h);
+        let periapsis = self.g.atan2(self.f);
+        let mut orbit = Orbit::new(
+            e,
+            self.p/(1.0 + e),
+            A::from_radians(2.0*self.h.hypot(self.k).atan()),
+            A::from_radians(o.rem_euclid(2.0*PI)),
+            A::from_radians((periapsis - o).rem_euclid(2.0*PI)),
+            0.0,
+            self.mu
+        );
+        orbit.t0 = self.t - orbit.mean_from_true(wrap(self.L - periapsis))/orbit.mean_motion();
+        return orbit;
+    }
+
+    pub fn from_state(pos: Pos, vel: Vel, mu: f64, t: f64) -> Result<ModifiedEquinoctialElements, EquinoctialError> {
+        let r = norm(pos);
+        let h_vec = cross(pos, vel);
+        let (k, h) = tilt(h_vec).ok_or(EquinoctialError::Retrograde)?;
+        let (f_hat, g_hat) = frame(k, h);
+        let e_vec = scale(sub(scale(pos, dot(vel, vel) - mu/r), scale(vel, dot(pos, vel))), 1.0/mu);
+        return Ok(ModifiedEquinoctialElements {
+            p: dot(h_vec, h_vec)/mu,
+            f: dot(e_vec, f_hat),
+            g: dot(e_vec, g_hat),
+            h,
+            k,
+            L: dot(pos, g_hat).atan2(dot(pos, f_hat)),
+            t,
+            mu,
+        });
+    }
+    pub fn state(&self) -> (Pos, Vel) {
+        let (f_hat, g_hat) = frame(self.k, self.h);
+        let (s, c) = self.L.sin_cos();
+        let r = self.p/(1.0 + self.f*c + self.g*s);
+        let speed = (self.mu/self.p).sqrt();
+        let pos = add(scale(f_hat, r*c), scale(g_hat, r*s));
+        let vel = add(scale(f_hat, -speed*(s + self.g)), scale(g_hat, speed*(c + self.f)));
+        return (pos, vel);
+    }
+
+    pub fn at(&self, t: f64) -> ModifiedEquinoctialElements {
+        if let Ok(elements) = EquinoctialElements::try_from(*self) {
+            return elements.at(t).into();
+        }
+        let orbit = self.to_orbit::<f64>();
+        let periapsis = self.g.atan2(self.f);
+        let L = orbit.true_from_mean(orbit.mean_anomaly(t)) + periapsis;
+        return ModifiedEquinoctialElements{ L, t, ..*self };
+    }
+    pub fn state_at(&self, t: f64) -> (Pos, Vel) {
+        return self.at(t).state();
+    }
+}
+
+impl From<EquinoctialElements> for ModifiedEquinoctialElements {
+    fn from(elements: EquinoctialElements) -> ModifiedEquinoctialElements {
+        let EquinoctialElements{ a, h, k, p, q, t, mu, .. } = elements;
+        let (x, y, _, _) = elements.plane(elements.F().E);
+        return ModifiedEquinoctialElements{ p: a*(1.0 - h*h - k*k), f: k, g: h, h: q, k: p, L: y.atan2(x), t, mu };
+    }
+}
+impl TryFrom<ModifiedEquinoctialElements> for EquinoctialElements {
+    type Error = EquinoctialError;
+    fn try_from(elements: ModifiedEquinoctialElements) -> Result<EquinoctialElements, EquinoctialError> {
+        let ModifiedEquinoctialElements{ p, f, g, h, k, L, t, mu } = elements;
+        let e2 = f*f + g*g;
+        if e2.sqrt() > 1.0 - PARABOLIC { // to_orbit would make it parabolic
+            return Err(EquinoctialError::NotElliptic);
+        }
+        let mut equinoctial = EquinoctialElements{ a: p/(1.0 - e2), h: g, k: f, p: k, q: h, lambda: 0.0, t, mu };
+        let (s, c) = L.sin_cos();
+        let r = p/(1.0 + f*c + g*s);
+        let F = equinoctial.eccentric_longitude(r*c, r*s);
+        equinoctial.lambda = F + g*F.cos() - f*F.sin();
+        return Ok(equinoctial);
+    }
+}
+
+/* tan(i/2)*sin(o), tan(i/2)*cos(o) from the angular momentum, None when it
+ * points straight down
+ */
+fn tilt(h: Pos) -> Option<(f64, f64)> {
+    let w = unit(h);
+    if 1.0 + w.2 <= 0.0 {
+        return None;
+    }
+    return Some((w.0/(1.0 + w.2), -w.1/(1.0 + w.2)));
+}
+// the equinoctial f and g directions from tan(i/2)*sin(o), tan(i/2)*cos(o)
+fn frame(p: f64, q: f64) -> (Pos, Pos) {
+    let s = 1.0 + p*p + q*q;
+    let f = ((1.0 - p*p + q*q)/s, 2.0*p*q/s, -2.0*p/s);
+    let g = (2.0*p*q/s, (1.0 + p*p - q*q)/s, 2.0*q/s);
+    return (f, g);
+}
+// -pi-pi
+fn wrap(angle: f64) -> f64 {
+    return angle - 2.0*PI*(angle/(2.0*PI)).round();
+}
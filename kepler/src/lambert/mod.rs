@@ -0,0 +1,246 @@
+/* README
+ * Lambert's problem: the orbit from r1 to r2 in a given time. Izzo's method
+ * (Revisiting Lambert's problem, 2015), the same steps as his pykep code. The
+ * geometry goes into lambda and the time into T, then x is solved for with
+ * Householder iterations and everything else follows from x.
+ *
+ * Going from r1 to r2 the short way or the long way round is set by the
+ * direction, prograde being counterclockwise looking down z. With enough time
+ * there are also two solutions for every number of whole revolutions.
+ */
+
+use core::fmt;
+use std::f64::consts::PI;
+use crate::{Orbit, Pos, Vel};
+use crate::angle::Angle;
+use crate::vector::*;
+
+const MAX_ITER: u32 = 15;
+const COLLINEAR: f64 = 1e-12; // sin of the transfer angle below this has no plane
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    Prograde,   // angular momentum along +z
+    Retrograde,
+}
+
+/* One solution. revs is the number of whole revolutions on the way, and for
+ * revs > 0 left is which of Izzo's two branches it came from.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LambertArc<A: Angle = f64> {
+    pub revs: u32,
+    pub left: bool,
+    pub v1: Vel,
+    pub v2: Vel,
+    pub orbit: Orbit<A>, // leaving r1 at t1
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LambertError {
+    TimeOfFlight,   // zero or negative
+    Collinear,      // r1 and r2 in a line through the focus, the plane is anything
+    NotConverged,
+}
+impl fmt::Display for LambertError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            LambertError::TimeOfFlight => write!(f, "time of flight has to be positive"),
+            LambertError::Collinear => write!(f, "r1 and r2 are collinear with the focus, the transfer plane is undefined"),
+            LambertError::NotConverged => write!(f, "lambert solver did not converge"),
+        };
+    }
+}
+impl std::error::Error for LambertError {}
+
+/* Every solution leaving r1 at t1 and getting to r2 tof later, up to max_revs
+ * revolutions. The single revolution one comes first, then left and right for
+ * 1 revolution and so on, as far as the time allows. Only the single revolution
+ * one failing is an error. If either branch for a number of revolutions doesn't
+ * converge the list ends before it, so every revolution count has both.
+ */
+pub fn lambert<A: Angle>(r1: Pos, r2: Pos, t1: f64, tof: f64, mu: f64, direction: Direction, max_revs: u32) -> Result<Vec<LambertArc<A>>, LambertError> {
+    if tof <= 0.0 || !tof.is_finite() {
+        return Err(LambertError::TimeOfFlight);
+    }
+    let (r1_len, r2_len) = (norm(r1), norm(r2));
+    let c = norm(sub(r2, r1));
+    let s = (r1_len + r2_len + c)/2.0; // semiperimeter
+
+    let (ir1, ir2) = (unit(r1), unit(r2));
+    let h = cross(ir1, ir2);
+    if norm(h) < COLLINEAR {
+        return Err(LambertError::Collinear);
+    }
+    let ih = unit(h);
+
+    // lambda < 0 is the long way round, past pi
+    let mut lambda = (1.0 - c/s).max(0.0).sqrt();
+    let (mut it1, mut it2) = if ih.2 < 0.0 {
+        lambda = -lambda;
+        (unit(cross(ir1, ih)), unit(cross(ir2, ih)))
+    } else {
+        (unit(cross(ih, ir1)), unit(cross(ih, ir2)))
+    };
+    if direction == Direction::Retrograde {
+        lambda = -lambda;
+        it1 = scale(it1, -1.0);
+        it2 = scale(it2, -1.0);
+    }
+
+    let izzo = Izzo{ lambda };
+    let T = (2.0*mu/(s*s*s)).sqrt()*tof;
+    let mut xs = vec![(0, false, izzo.single(T)?)];
+    for revs in 1..=izzo.max_revs(T).min(max_revs) {
+        let N = revs as f64;
+        let left = ((N*PI + PI)/(8.0*T)).powf(2.0/3.0);
+        let right = ((8.0*T)/(N*PI)).powf(2.0/3.0);
+        let left = izzo.householder(T, (left - 1.0)/(left + 1.0), revs, 1e-8);
+        let right = izzo.householder(T, (right - 1.0)/(right + 1.0), revs, 1e-8);
+        match (left, right) {
+            (Ok(left), Ok(right)) => xs.extend([(revs, true, left), (revs, false, right)]),
+            _ => break, // more revolutions only get harder, keep the pairs that converged
+        }
+    }
+
+    // velocities from x, radial and tangential at each end
+    let gamma = (mu*s/2.0).sqrt();
+    let rho = (r1_len - r2_len)/c;
+    let sigma = (1.0 - rho*rho).max(0.0).sqrt();
+    return Ok(xs.into_iter().map(|(revs, left, x)| {
+        let y = (1.0 - lambda*lambda + lambda*lambda*x*x).sqrt();
+        let vr1 = gamma*((lambda*y - x) - rho*(lambda*y + x))/r1_len;
+        let vr2 = -gamma*((lambda*y - x) + rho*(lambda*y + x))/r2_len;
+        let vt = gamma*sigma*(y + lambda*x);
+        let v1 = add(scale(ir1, vr1), scale(it1, vt/r1_len));
+        let v2 = add(scale(ir2, vr2), scale(it2, vt/r2_len));
+        return LambertArc{ revs, left, v1, v2, orbit: Orbit::from_state(r1, v1, mu, t1) };
+    }).collect());
+}
+
+// the transfer geometry, lambda^2 = 1 - c/s
+struct Izzo {
+    lambda: f64,
+}
+impl Izzo {
+    // x for no whole revolutions, from the starting guesses in the paper
+    fn single(&self, T: f64) -> Result<f64, LambertError> {
+        let l = self.lambda;
+        let T00 = l.acos() + l*(1.0 - l*l).sqrt();          // x = 0
+        let T1 = 2.0/3.0*(1.0 - l*l*l);                     // x = 1
+        let x0 = if T >= T00 {
+            -(T - T00)/(T - T00 + 4.0)
+        } else if T <= T1 {
+            T1*(T1 - T)/(2.0/5.0*(1.0 - l*l*l*l*l)*T) + 1.0
+        } else {
+            (T/T00).powf(std::f64::consts::LN_2/(T1/T00).ln()) - 1.0
+        };
+        return self.householder(T, x0, 0, 1e-5);
+    }
+
+    /* most revolutions there's enough time for. T(x) has a minimum for each
+     * revs > 0 and below it there's no solution, found with Halley on dT/dx = 0.
+     */
+    fn max_revs(&self, T: f64) -> u32 {
+        let l = self.lambda;
+        let mut revs = (T/PI).floor() as u32;
+        let T0 = l.acos() + l*(1.0 - l*l).sqrt() + revs as f64*PI;
+        if revs > 0 && T < T0 {
+            let (mut x, mut T_min) = (0.0, T0);
+            for _ in 0..12 {
+                let (dT, ddT, dddT) = self.derivatives(x, T_min);
+                let x_new = if dT != 0.0 { x - dT*ddT/(ddT*ddT - dT*dddT/2.0) } else { x };
+                if (x - x_new).abs() < 1e-13 { break }
+                x = x_new;
+                T_min = self.tof(x, revs);
+            }
+            if T_min > T { revs -= 1 }
+        }
+        return revs;
+    }
+
+    // third order Householder on T(x) = T
+    fn householder(&self, T: f64, mut x: f64, revs: u32, precision: f64) -> Result<f64, LambertError> {
+        for _ in 0..MAX_ITER {
+            let tof = self.tof(x, revs);
+            let (dT, ddT, dddT) = self.derivatives(x, tof);
+            let delta = tof - T;
+            let dT2 = dT*dT;
+            let x_new = x - delta*(dT2 - delta*ddT/2.0)/(dT*(dT2 - delta*ddT) + dddT*delta*delta/6.0);
+            let step = (x - x_new).abs();
+            x = x_new;
+            if step < precision {
+                return Ok(x);
+            }
+        }
+        return Err(LambertError::NotConverged);
+    }
+
+    // first three derivatives of T at x, given T there
+    fn derivatives(&self, x: f64, T: f64) -> (f64, f64, f64) {
+        let l = self.lambda;
+        let (l2, l3) = (l*l, l*l*l);
+        let umx2 = 1.0 - x*x;
+        let y = (1.0 - l2*umx2).sqrt();
+        let (y2, y3) = (y*y, y*y*y);
+        let dT = (3.0*T*x - 2.0 + 2.0*l3*x/y)/umx2;
+        let ddT = (3.0*T + 5.0*x*dT + 2.0*(1.0 - l2)*l3/y3)/umx2;
+        let dddT = (7.0*x*ddT + 8.0*dT - 6.0*(1.0 - l2)*l2*l3*x/y3/y2)/umx2;
+        return (dT, ddT, dddT);
+    }
+
+    /* non-dimensional time of flight for x. Battin's series right next to x = 1
+     * (parabolic), Lagrange a bit further out and Lancaster everywhere else.
+     */
+    fn tof(&self, x: f64, revs: u32) -> f64 {
+        const BATTIN: f64 = 0.01;
+        const LAGRANGE: f64 = 0.2;
+        let l = self.lambda;
+        let N = revs as f64;
+        let dist = (x - 1.0).abs();
+
+        if dist < LAGRANGE && dist > BATTIN {
+            let a = 1.0/(1.0 - x*x);
+            if a > 0.0 {
+                let alpha = 2.0*x.acos();
+                let beta = (2.0*(l*l/a).sqrt().asin()).copysign(l);
+                return a*a.sqrt()*((alpha - alpha.sin()) - (beta - beta.sin()) + 2.0*PI*N)/2.0;
+            }
+            let alpha = 2.0*x.acosh();
+            let beta = (2.0*(-l*l/a).sqrt().asinh()).copysign(l);
+            return -a*(-a).sqrt()*((beta - beta.sinh()) - (alpha - alpha.sinh()))/2.0;
+        }
+
+        let E = x*x - 1.0;
+        let rho = E.abs();
+        let z = (1.0 + l*l*E).sqrt();
+        if dist < BATTIN {
+            let eta = z - l*x;
+            let S1 = 0.5*(1.0 - l - x*eta);
+            let Q = 4.0/3.0*hypergeometric(S1, 1e-11);
+            return (eta*eta*eta*Q + 4.0*l*eta)/2.0 + N*PI/rho.powf(1.5);
+        }
+
+        let y = rho.sqrt();
+        let g = x*z - l*E;
+        let d = if E < 0.0 {
+            N*PI + g.acos()
+        } else {
+            (y*(z - l*x) + g).ln()
+        };
+        return (x - l*z - d/y)/E;
+    }
+}
+
+// 2F1(3, 1, 5/2, z), for Battin's series
+fn hypergeometric(z: f64, precision: f64) -> f64 {
+    let (mut sum, mut term): (f64, f64) = (1.0, 1.0);
+    let mut j = 0.0;
+    while term.abs() > precision {
+        term *= (3.0 + j)*(1.0 + j)/(2.5 + j)*z/(j + 1.0);
+        sum += term;
+        j += 1.0;
+    }
+    return sum;
+}
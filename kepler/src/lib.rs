@@ -1,145 +1,317 @@
 /* README
- * This is a set of orbital parameters. Currently everything is floats, but
- * This should change to fixed point numbers due to the loss of precision.
+ * This is a set of orbital parameters. Orbits themselves are floats, but
+ * absolute positions are fixed point (see position) due to the loss of precision.
  * Space has no center so it should not be more accurate depending on the time
- * or location.
+ * or location. Distances are in metres.
  */
 #![allow(non_snake_case)]
 #![allow(unused)]
+#![allow(clippy::needless_return)]
 
 pub mod angle;
+pub mod position;
+pub mod vector;
+pub mod state;
+pub mod anomaly;
+pub mod derived;
+pub mod builder;
+pub mod equinoctial;
+pub mod universal;
+pub mod solver;
+pub mod batch;
+pub mod body;
+pub mod soi;
+pub mod burn;
+pub mod transfer;
+pub mod lambert;
+pub mod porkchop;
+#[cfg(feature = "parallel")]
+pub mod catalog;
 
+use angle::Angle;
+use position::{Position, Displacement};
+use solver::{Solver, KeplerSolver, Solution, KeplerError};
 use std::{f64::consts::PI, char::MAX};
 
-pub fn rot_x(pos: (f64, f64, f64), angle: f64) -> (f64, f64, f64) {
+pub type Pos = (f64, f64, f64);
+pub type Vel = (f64, f64, f64);
+
+pub fn rot_x<A: Angle>(pos: (f64, f64, f64), angle: A) -> (f64, f64, f64) {
     let x     = pos.0;
     let mut y = pos.1;
     let mut z = pos.2;
 
     let r = y.hypot(z);         // radius from axis
-    let mut theta = z.atan2(y); // angle from plane
-    theta += angle;                  // applying rotating
+    let mut theta = A::atan2(z, y); // angle from plane
+    theta = theta + angle;                  // applying rotating
 
-    y = r*theta.cos();
-    z = r*theta.sin();
+    let (sin, cos) = theta.sin_cos(); // one CORDIC run for fixed point angles
+    y = r*cos;
+    z = r*sin;
     return ( x, y, z );
 }
-pub fn rot_y(pos: (f64, f64, f64), angle: f64) -> (f64, f64, f64) {
+pub fn rot_y<A: Angle>(pos: (f64, f64, f64), angle: A) -> (f64, f64, f64) {
     let mut x = pos.0;
     let y     = pos.1;
     let mut z = pos.2;
         
     let r = x.hypot(z);         // radius from axis
-    let mut theta = x.atan2(z); // angle from axis
-    theta += angle;                  // applying rotating
+    let mut theta = A::atan2(x, z); // angle from axis
+    theta = theta + angle;                  // applying rotating
 
-    z = r*theta.cos();
-    x = r*theta.sin();
+    let (sin, cos) = theta.sin_cos();
+    z = r*cos;
+    x = r*sin;
     return ( x, y, z );
 }
-pub fn rot_z(pos: (f64, f64, f64), angle: f64) -> (f64, f64, f64) {
+pub fn rot_z<A: Angle>(pos: (f64, f64, f64), angle: A) -> (f64, f64, f64) {
     let mut x = pos.0;
     let mut y = pos.1;          // coordinates
     let z     = pos.2;
         
     let r = x.hypot(y);         // radius from axis
-    let mut theta = y.atan2(x); // angle from axis
-    theta += angle;                  // applying rotating
+    let mut theta = A::atan2(y, x); // angle from axis
+    theta = theta + angle;                  // applying rotating
     
-    x = r*theta.cos();
-    y = r*theta.sin();               // calculating change
+    let (sin, cos) = theta.sin_cos();
+    x = r*cos;
+    y = r*sin;                       // calculating change
     return ( x, y, z );
 }
 
-pub struct Orbit {
+/* how positions are worked out. Classic is E/H/D depending on e, except within
+ * NEAR_PARABOLIC of e = 1 where it goes universal too: E - e*sin(E) and
+ * e*sinh(H) - H cancel to nothing there and the error grows like 1/(e-1)^2.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Propagator {
+    #[default]
+    Classic,
+    Universal,
+}
+pub const NEAR_PARABOLIC: f64 = 1e-2;
+
+// angles can be f64 radians, r32 or r64. a plain Orbit is Orbit<f64>
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Orbit<A: Angle = f64> {
     pub e: f64, // eccentricity                     0-1
     pub a: f64, // semimajor axis
     pub b: f64, // semiminor axis
+    pub q: f64, // periapsis distance, the only size that works for parabolic orbits
 
-    pub i: f64, // inclination                      0-pi
-    pub o: f64, // longitude of the ascending node  0-2pi
-    pub w: f64, // argument of periapsis            0-2pi
+    pub i: A, // inclination                      0-pi
+    pub o: A, // longitude of the ascending node  0-2pi
+    pub w: A, // argument of periapsis            0-2pi
 
     pub t0: f64, // time of periapsis passage
+    pub mu: f64, // gravitational parameter of the central body
+
+    pub propagator: Propagator,
+    pub solver: Solver, // for E, see solver
 }
-impl Orbit {
+impl<A: Angle> Orbit<A> {
     // TODO: change a/b to periapsis. then calculate a/b
-    pub fn new( mut e: f64, periapsis: f64, i: f64, o: f64, w: f64, t0: f64 ) -> Orbit {
+    // unchecked, see builder for one that says what is wrong
+    pub fn new( mut e: f64, periapsis: f64, i: A, o: A, w: A, t0: f64, mu: f64 ) -> Orbit<A> {
         e = e.abs(); // safety feature
-        let a = Orbit::a_from_periapsis(periapsis, e);
+        let a = Self::a_from_periapsis(periapsis, e);
         return Orbit { 
             e, 
             a,
-            b: Orbit::b_from_a(a, e), 
+            b: Self::b_from_a(a, e), 
+            q: periapsis,
             i, 
             o, 
             w,
             t0,
+            mu,
+            propagator: Propagator::Classic,
+            solver: Solver::Newton,
         }
     }
 
     /* x+ is the reference direction
      * y+ is theta+
      * z+ is 'north'
+     * M wraps if given as r32/r64, so only f64 makes sense for hyperbolic orbits.
      */
-    pub fn pos(&self, M: f64) -> (f64, f64, f64) {
-        let mut pos = if self.e < 1.0 { // if elliptic
+    pub fn pos(&self, M: A) -> Pos {
+        return self.pos_mean(M.to_radians());
+    }
+    // position at time t, works out M from t0 and mu
+    pub fn pos_at(&self, t: f64) -> Pos {
+        return self.pos_mean(self.mean_anomaly(t));
+    }
+    fn pos_mean(&self, M: f64) -> Pos {
+        if self.universal() {
+            return self.state_mean(M).0;
+        }
+        let pos = if self.e < 1.0 { // if elliptic
             let E = self.E(M);
             self.pos_elliptic(E)
+        } else if self.e == 1.0 {                        // if parabolic
+            let D = Self::D(M);
+            self.pos_parabolic(D)
         } else {                                         // if hyperbolic
-            let H = self.H(M);
+            let H = self.H(M).E;
             self.pos_hyperbolic(H)
         };
-        
+        return self.orient(pos);
+    }
+
+    // velocity needs mu, same axes as pos
+    pub fn vel(&self, M: A) -> Vel {
+        return self.state(M).1;
+    }
+    pub fn vel_at(&self, t: f64) -> Vel {
+        return self.state_at(t).1;
+    }
+    // position and velocity from a single E/H
+    pub fn state(&self, M: A) -> (Pos, Vel) {
+        return self.state_mean(M.to_radians());
+    }
+    pub fn state_at(&self, t: f64) -> (Pos, Vel) {
+        return self.state_mean(self.mean_anomaly(t));
+    }
+    fn state_mean(&self, M: f64) -> (Pos, Vel) {
+        let anomaly = self.anomaly(M);
+        return self.state_from(M, anomaly.E);
+    }
+
+    /* Checked versions
+     * pos/state return whatever estimate the solver had when it ran out of
+     * iterations. These return an error instead.
+     */
+    pub fn try_pos(&self, M: A) -> Result<Pos, KeplerError> {
+        return Ok(self.try_state(M)?.0);
+    }
+    pub fn try_pos_at(&self, t: f64) -> Result<Pos, KeplerError> {
+        return Ok(self.try_state_at(t)?.0);
+    }
+    pub fn try_state(&self, M: A) -> Result<(Pos, Vel), KeplerError> {
+        return self.try_state_mean(M.to_radians());
+    }
+    pub fn try_state_at(&self, t: f64) -> Result<(Pos, Vel), KeplerError> {
+        return self.try_state_mean(self.mean_anomaly(t));
+    }
+    fn try_state_mean(&self, M: f64) -> Result<(Pos, Vel), KeplerError> {
+        let anomaly = self.anomaly(M);
+        if !anomaly.converged || !anomaly.E.is_finite() {
+            return Err(KeplerError{ iter: anomaly.iter, residual: self.residual(M, anomaly.E), e: self.e, M });
+        }
+        return Ok(self.state_from(M, anomaly.E));
+    }
+
+    // E, D, H or X depending on the orbit
+    fn anomaly(&self, M: f64) -> Solution {
+        if self.universal() {
+            return self.X_at(self.since_periapsis(M));
+        } else if self.e < 1.0 {
+            return self.solver.solve(self.e, M);
+        } else if self.e == 1.0 {
+            return Solution{ E: Self::D(M), iter: 0, converged: true }; // closed form
+        }
+        return self.H(M);
+    }
+    fn state_from(&self, M: f64, anomaly: f64) -> (Pos, Vel) {
+        let (pos, vel) = if self.universal() {
+            self.state_universal(self.since_periapsis(M), anomaly)
+        } else if self.e < 1.0 { // if elliptic
+            (self.pos_elliptic(anomaly), self.vel_elliptic(anomaly))
+        } else if self.e == 1.0 {                        // if parabolic
+            (self.pos_parabolic(anomaly), self.vel_parabolic(anomaly))
+        } else {                                         // if hyperbolic
+            (self.pos_hyperbolic(anomaly), self.vel_hyperbolic(anomaly))
+        };
+        return (self.orient(pos), self.orient(vel));
+    }
+    // how far off Kepler's equation the anomaly is, in M
+    fn residual(&self, M: f64, anomaly: f64) -> f64 {
+        if self.universal() {
+            return self.residual_universal(self.since_periapsis(M), anomaly)*self.mean_motion();
+        } else if self.e < 1.0 {
+            return anomaly - self.e*anomaly.sin() - M;
+        } else if self.e == 1.0 {
+            return anomaly + anomaly.powi(3)/3.0 - M;
+        }
+        return self.e*anomaly.sinh() - anomaly - M;
+    }
+
+    // Classic orbits this close to parabolic go universal, exactly parabolic has Barker
+    pub(crate) fn universal(&self) -> bool {
+        if self.propagator == Propagator::Universal { return true }
+        return self.e != 1.0 && (self.e - 1.0).abs() < NEAR_PARABOLIC;
+    }
+
+    // from the orbital plane to the reference frame
+    fn orient(&self, mut pos: Pos) -> Pos {
         pos = rot_z(pos, self.w); // apply argument of periapsis
         pos = rot_x(pos, self.i); // apply inclination
         pos = rot_z(pos, self.o); // apply longitude of the ascending node
+        return pos;
+    }
 
-        return (pos.0 as f64, pos.1 as f64, pos.2 as f64);
-    }
-    /* E calculation
-     * Newton-Raphson method, but made it stable. Getting the remainder of the
-     * result is required due to the function being non-continuous.
-     * Very slowly starts to break if e=1 and M=0.
-     * 
-     * max average steps: 4.87125 (e = 1, M = 0-2PI)
-     * min average steps: 0.00000 (e = 0)
-     * max steps needed:  N/A     (e = 1, M = 0)
-     * min steps needed:  0       (e = 0)
+    /* n = sqrt(mu/|a|^3), the same for both branches since a is negative if hyperbolic
+     * a is infinite for parabolic orbits so Barker's sqrt(mu/(2q^3)) is used instead.
+     * its M = D + D^3/3 isn't the limit of the other two, only positions in time are.
      */
-    fn E(&self, M: f64) -> f64 {
-        const PRECISION: f64 = 9e-16;   // min stable number
-        const MAX_ITER: u32 = 100;      // if e = ~1 and M = ~0
-        let mut E: f64 = M;  // initial estimate
-
-        for i in 0..MAX_ITER {
-            let E_next = M + self.e*E.sin(); // calculate next guess
-            let E_diff = E_next - E;
-            
-            if E_diff.abs() < PRECISION {
-                return E_next;
-            } else {
-                let E_prime = 1.0 - self.e*E.cos();
-                E = E + ( E_diff/E_prime ) % 1.4; // 1.4 causes the best results
-            }
+    pub fn mean_motion(&self) -> f64 {
+        if self.e == 1.0 {
+            return (self.mu/(2.0*self.q.powi(3))).sqrt();
         }
+        return (self.mu/self.a.abs().powi(3)).sqrt();
+    }
+    /* M = n(t-t0). elliptic orbits wrap so M stays precise far from t0. It wraps
+     * to -pi-pi so just before periapsis is a small negative M rather than almost
+     * a whole period, which matters once the period is huge and e is close to 1.
+     */
+    pub fn mean_anomaly(&self, t: f64) -> f64 {
+        let M = self.mean_motion()*(t - self.t0);
+        if self.e < 1.0 {
+            return M - 2.0*PI*(M/(2.0*PI)).round();
+        }
+        return M;
+    }
 
-        return E;
+    // same as pos, in metres from the focus
+    pub fn pos_fixed(&self, M: A) -> Displacement {
+        return Displacement::from_f64(self.pos(M));
+    }
+    // absolute position given where the focus is
+    pub fn pos_around(&self, focus: Position, M: A) -> Position {
+        return focus + self.pos_fixed(M);
+    }
+    pub fn pos_fixed_at(&self, t: f64) -> Displacement {
+        return Displacement::from_f64(self.pos_at(t));
+    }
+    pub fn pos_around_at(&self, focus: Position, t: f64) -> Position {
+        return focus + self.pos_fixed_at(t);
+    }
+    // E calculation, which solver is up to the orbit
+    fn E(&self, M: f64) -> f64 {
+        return self.solver.solve(self.e, M).E;
     }
-    fn pos_elliptic(&self, E: f64) -> ( f64, f64, f64 ) {
+    fn pos_elliptic(&self, E: f64) -> Pos {
         let x = self.a*(E.cos()-self.e);
         let y = self.b*E.sin();
 
         return ( x, y, 0.0 );
     }
+    fn vel_elliptic(&self, E: f64) -> Vel {
+        let E_dot = self.mean_motion()/(1.0 - self.e*E.cos()); // dE/dt from M = E - e*sin(E)
+        let x = -self.a*E.sin()*E_dot;
+        let y = self.b*E.cos()*E_dot;
+
+        return ( x, y, 0.0 );
+    }
     /* H calculation
      * Newton-Raphson method. Due to hyperbolic functions being exponential, the calculations
      * are more complex and are reordered but still the same. Generally doesn't take more than
      * 10 iterations.
      * Only breaks if you travel faster than light next to a black hole.
      */
-    fn H(&self, M: f64) -> f64 {
-        if M == 0.0 { return 0.0 }
+    fn H(&self, M: f64) -> Solution {
+        if M == 0.0 { return Solution{ E: 0.0, iter: 0, converged: true } }
 
         const PRECISION: f64 = 4e-15;   // min stable number
         const MAX_ITER: u32 = 100;      // for safety
@@ -156,7 +328,7 @@ impl Orbit {
             let H_diff = H_next - H;
             
             if H_diff.abs() < PRECISION {
-                return H_next;
+                return Solution{ E: H_next, iter: i, converged: true };
             } else {
                 // let H_prime = -1.0 + self.e*H.cosh(); // H.cosh() can be inf
                 // H = H - ( H_diff/H_prime );
@@ -164,20 +336,52 @@ impl Orbit {
             }
             
         }
-        return H;
+        return Solution{ E: H, iter: MAX_ITER, converged: false };
     }
-    fn pos_hyperbolic(&self, H: f64) -> ( f64, f64, f64 ) {
+    fn pos_hyperbolic(&self, H: f64) -> Pos {
         let x = self.a*(H.cosh()-self.e);
         let y = self.b*H.sinh();
 
         return ( x, y, 0.0 );
     }
+    fn vel_hyperbolic(&self, H: f64) -> Vel {
+        let H_dot = self.mean_motion()/(self.e*H.cosh() - 1.0); // dH/dt from M = e*sinh(H) - H
+        let x = self.a*H.sinh()*H_dot;
+        let y = self.b*H.cosh()*H_dot;
+
+        return ( x, y, 0.0 );
+    }
+
+    /* D calculation
+     * Barker's equation M = D + D^3/3 where D = tan(nu/2). It's a depressed cubic
+     * so there is a closed form, no iterating. Solved for |M| since the positive
+     * branch doesn't cancel.
+     */
+    fn D(M: f64) -> f64 {
+        let A = 1.5*M.abs();
+        let B = (A + (A*A + 1.0).sqrt()).cbrt();
+        let D = B - 1.0/B;
+        return if M.is_sign_negative() {-D} else {D};
+    }
+    fn pos_parabolic(&self, D: f64) -> Pos {
+        let x = self.q*(1.0 - D*D);
+        let y = 2.0*self.q*D;
+
+        return ( x, y, 0.0 );
+    }
+    fn vel_parabolic(&self, D: f64) -> Vel {
+        let D_dot = self.mean_motion()/(1.0 + D*D); // dD/dt from M = D + D^3/3
+        let x = -2.0*self.q*D*D_dot;
+        let y = 2.0*self.q*D_dot;
+
+        return ( x, y, 0.0 );
+    }
 
     fn a_from_periapsis(periapsis: f64, e: f64) -> f64 {
         return periapsis/(1.0-e);
     }
     fn b_from_a(a: f64, e: f64) -> f64 {
-        return a*(1.0-e*e).abs().sqrt();
+        return a.abs()*(1.0-e*e).abs().sqrt(); // positive so hyperbolic orbits go the same way
     }
 
     fn a(e: f64, b: f64) -> f64 {
@@ -190,11 +394,13 @@ impl Orbit {
         return (1.0-( (b*b)/(a*a) )).sqrt();
     }
     
+    // infinite for parabolic and hyperbolic orbits, they never come back
     pub fn apoapsis(&self) -> f64 {
-        return self.a - self.periapsis();
+        if self.e >= 1.0 { return f64::INFINITY }
+        return self.a*(1.0 + self.e);
     }
     pub fn periapsis(&self) -> f64 {
-        return ( self.a - self.a*self.e )/2.0
+        return self.q;
     }
 }
 
@@ -217,4 +423,9 @@ impl Stat {
     pub fn mean(&self) -> f64 {
         return self.total / self.count as f64;
     } 
+}
+impl Default for Stat {
+    fn default() -> Stat {
+        return Stat::new();
+    }
 }
\ No newline at end of file
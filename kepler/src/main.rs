@@ -1,6 +1,7 @@
 #![allow(unused)]
 #![allow(non_snake_case)]
 #![allow(arithmetic_overflow)]
+#![allow(clippy::needless_return)]
 
 
 use std::{ops::{Add, Mul}, time::Instant, f64::consts::PI};
@@ -8,13 +9,13 @@ use kepler::*;
 use std::num::Wrapping;
 
 fn main() {
-    let orbit = Orbit::new(0.8, 5.0, PI/4.0, 0.0, 0.0, 0.0);
+    let orbit = Orbit::new(0.8, 5.0, PI/4.0, 0.0, 0.0, 0.0, 1.0);
     const COUNT: u32 = 100;
     graph(&orbit, COUNT, 200, 24);
     print_coords(&orbit, COUNT);
 }
 fn pi_test() {
-    let pi = 1 as f64;
+    let pi = 1.0_f64;
     let mut one = pi/PI;
 
     let mut min_idx = -1;
@@ -33,7 +34,6 @@ fn pi_test() {
     }
     println!("MIN: {} with {}", min_idx, min);
 }
-
 fn graph(orbit: &Orbit, count: u32, px: u32, fps: u32) {
     use kepler::*;
     use plotters::prelude::*;
@@ -51,6 +51,13 @@ fn graph(orbit: &Orbit, count: u32, px: u32, fps: u32) {
         .build_cartesian_3d(-size..size, -size..size, -size..size)
         .unwrap();
 
+    // the orbit doesn't change between frames
+    let step: f64 = (2.0*PI/(count as f64));
+    let ts: Vec<f64> = (0..count+1)
+        .map(|j| orbit.t0 + step*((j as i32-(count>>1) as i32) as f64)/orbit.mean_motion())
+        .collect();
+    let points = orbit.pos_many(&ts);
+
     //iterating
     for i in 0..=100 {
         // background
@@ -65,9 +72,8 @@ fn graph(orbit: &Orbit, count: u32, px: u32, fps: u32) {
         });
 
         // orbit render. the renderer has their axies messed up
-        let step: f64 = (2.0*PI/(count as f64));
         chart.draw_series(LineSeries::new(
-        (0..count+1).map(|j| {let c = orbit.pos(step*((j as i32-(count>>1) as i32) as f64)); (c.0, c.2, -c.1)}),
+        points.iter().map(|c| (c.0, c.2, -c.1)),
         &BLACK
         )).unwrap();
 
@@ -91,13 +97,13 @@ fn graph(orbit: &Orbit, count: u32, px: u32, fps: u32) {
 }
 fn print_coords(orbit: &Orbit, mut count: u32) {
     let step: f64 = (4.0*PI/(count as f64));
-    count = count*10;
+    count *= 10;
     
 
-    for i in 0..count {
-        let M = step*((i as i32-(count>>1) as i32) as f64);
-        
-        let pos = orbit.pos(M);
+    let ts: Vec<f64> = (0..count)
+        .map(|i| orbit.t0 + step*((i as i32-(count>>1) as i32) as f64)/orbit.mean_motion())
+        .collect();
+    for pos in orbit.pos_many(&ts) {
         println!("({}, {})", pos.0, pos.1);
     }
 }
\ No newline at end of file
@@ -0,0 +1,156 @@
+/* README
+ * Porkchop plots for picking a launch window. Every departure time is paired
+ * with every time of flight, the planets are placed with their orbits and the
+ * single revolution Lambert transfer between them gives the excess speeds:
+ * C3 = v_inf^2 leaving and v_inf arriving. Cells Lambert can't solve are NaN.
+ *
+ * render draws both as contours over departure and time of flight, in days,
+ * with C3 in km^2/s^2 and v_inf in km/s the way they are usually quoted. The
+ * contours are marching squares over the grid, plotters has no contour series.
+ */
+
+use core::fmt;
+use std::error::Error;
+use std::path::Path;
+use plotters::prelude::*;
+use crate::Orbit;
+use crate::angle::Angle;
+use crate::lambert::{lambert, Direction};
+use crate::vector::*;
+
+const DAY: f64 = 86400.0;
+
+/* grid[i][j] is departures[i] with tofs[j]. C3 in m^2/s^2, v_inf in m/s */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Porkchop {
+    pub departures: Vec<f64>,
+    pub tofs: Vec<f64>,
+    pub c3: Vec<Vec<f64>>,
+    pub v_inf: Vec<Vec<f64>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PorkchopError {
+    Axis,   // departures or tofs don't span anything, fewer than two or the ends equal
+}
+impl fmt::Display for PorkchopError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            PorkchopError::Axis => write!(f, "porkchop needs at least two different departures and times of flight to draw"),
+        };
+    }
+}
+impl Error for PorkchopError {}
+
+pub fn porkchop<A: Angle>(from: &Orbit<A>, to: &Orbit<A>, departures: &[f64], tofs: &[f64], direction: Direction) -> Porkchop {
+    let mut c3 = vec![vec![f64::NAN; tofs.len()]; departures.len()];
+    let mut v_inf = c3.clone();
+
+    for (i, &t1) in departures.iter().enumerate() {
+        let (r1, planet1) = from.state_at(t1);
+        for (j, &tof) in tofs.iter().enumerate() {
+            let (r2, planet2) = to.state_at(t1 + tof);
+            if let Ok(arcs) = lambert::<A>(r1, r2, t1, tof, from.mu, direction, 0) {
+                let leaving = norm(sub(arcs[0].v1, planet1));
+                c3[i][j] = leaving*leaving;
+                v_inf[i][j] = norm(sub(arcs[0].v2, planet2));
+            }
+        }
+    }
+    return Porkchop{ departures: departures.to_vec(), tofs: tofs.to_vec(), c3, v_inf };
+}
+
+impl Porkchop {
+    // (departure, tof, C3) of the lowest C3 on the grid
+    pub fn min_c3(&self) -> Option<(f64, f64, f64)> {
+        let mut best: Option<(f64, f64, f64)> = None;
+        for (i, row) in self.c3.iter().enumerate() {
+            for (j, &c3) in row.iter().enumerate() {
+                if c3.is_finite() && best.is_none_or(|best| c3 < best.2) {
+                    best = Some((self.departures[i], self.tofs[j], c3));
+                }
+            }
+        }
+        return best;
+    }
+
+    /* png or svg, going by the extension. Levels are in km^2/s^2 for C3 and km/s
+     * for v_inf, one line each. Both axes have to span something, see PorkchopError.
+     */
+    pub fn render(&self, path: &Path, size: (u32, u32), c3_levels: &[f64], v_inf_levels: &[f64]) -> Result<(), Box<dyn Error>> {
+        let spans = |t: &[f64]| t.len() >= 2 && t[0] != t[t.len()-1];
+        if !spans(&self.departures) || !spans(&self.tofs) {
+            return Err(Box::new(PorkchopError::Axis));
+        }
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("svg")) {
+            return self.draw(SVGBackend::new(path, size).into_drawing_area(), c3_levels, v_inf_levels);
+        }
+        return self.draw(BitMapBackend::new(path, size).into_drawing_area(), c3_levels, v_inf_levels);
+    }
+
+    fn draw<DB: DrawingBackend>(&self, area: DrawingArea<DB, plotters::coord::Shift>, c3_levels: &[f64], v_inf_levels: &[f64]) -> Result<(), Box<dyn Error>>
+    where DB::ErrorType: 'static {
+        area.fill(&WHITE)?;
+        let days = |t: &[f64]| (t[0]/DAY, t[t.len()-1]/DAY);
+        let (x0, x1) = days(&self.departures);
+        let (y0, y1) = days(&self.tofs);
+        let mut chart = ChartBuilder::on(&area)
+            .caption("C3 (km^2/s^2) and arrival v_inf (km/s)", ("sans-serif", 20))
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(50)
+            .build_cartesian_2d(x0..x1, y0..y1)?;
+        chart.configure_mesh()
+            .x_desc("departure (days)")
+            .y_desc("time of flight (days)")
+            .draw()?;
+
+        let c3: Vec<Vec<f64>> = self.c3.iter().map(|row| row.iter().map(|c3| c3/1e6).collect()).collect();
+        let v_inf: Vec<Vec<f64>> = self.v_inf.iter().map(|row| row.iter().map(|v| v/1e3).collect()).collect();
+        for (grid, levels, color, name) in [(&c3, c3_levels, BLUE, "C3"), (&v_inf, v_inf_levels, RED, "v_inf")] {
+            for &level in levels {
+                let segments = self.contour(grid, level);
+                chart.draw_series(segments.iter().map(|&(a, b)| PathElement::new(vec![a, b], color)))?;
+                if let Some(&(a, _)) = segments.get(segments.len()/2) {
+                    chart.draw_series(std::iter::once(Text::new(format!("{}", level), a, ("sans-serif", 12).into_font().color(&color))))?;
+                }
+            }
+            chart.draw_series(std::iter::empty::<PathElement<(f64, f64)>>())?
+                .label(name)
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+        }
+        chart.configure_series_labels().background_style(WHITE).border_style(BLACK).draw()?;
+        area.present()?;
+        return Ok(());
+    }
+
+    /* marching squares, line segments in days where grid crosses level. corners
+     * go round each cell so crossings pair up in order, a saddle just takes the
+     * first pairing. cells touching NaN are skipped.
+     */
+    fn contour(&self, grid: &[Vec<f64>], level: f64) -> Vec<((f64, f64), (f64, f64))> {
+        let mut segments = Vec::new();
+        let point = |i: usize, j: usize| (self.departures[i]/DAY, self.tofs[j]/DAY);
+        for i in 0..self.departures.len().saturating_sub(1) {
+            for j in 0..self.tofs.len().saturating_sub(1) {
+                let corners = [(i, j), (i+1, j), (i+1, j+1), (i, j+1)];
+                if corners.iter().any(|&(i, j)| !grid[i][j].is_finite()) { continue }
+
+                let mut crossings = Vec::new();
+                for k in 0..4 {
+                    let (a, b) = (corners[k], corners[(k+1) % 4]);
+                    let (va, vb) = (grid[a.0][a.1], grid[b.0][b.1]);
+                    if (va < level) != (vb < level) {
+                        let f = (level - va)/(vb - va);
+                        let (pa, pb) = (point(a.0, a.1), point(b.0, b.1));
+                        crossings.push((pa.0 + f*(pb.0 - pa.0), pa.1 + f*(pb.1 - pa.1)));
+                    }
+                }
+                for pair in crossings.chunks(2) {
+                    if let [a, b] = pair { segments.push((*a, *b)) }
+                }
+            }
+        }
+        return segments;
+    }
+}
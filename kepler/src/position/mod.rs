@@ -0,0 +1,145 @@
+/* README
+ * Fixed point coordinates. Every axis is an i64 of millimetres, which covers about
+ * 60000 AU either side of the origin with the same precision everywhere. Floats on
+ * either side are metres.
+ * A Position is a point in space and a Displacement is the difference between two.
+ * Points can't be added to each other, only moved by a displacement.
+ * A Displacement is an i64 as well, so two points on opposite sides only have one
+ * between them if each is within about 30000 AU. Arithmetic past that panics, in
+ * release builds too, instead of wrapping round to the far side of the system.
+ */
+
+use std::ops;
+
+const UNIT: f64 = 1000.0; // units per metre
+
+fn to_fixed(num: f64) -> i64 {
+    return (num*UNIT).round() as i64; // saturates instead of wrapping
+}
+fn to_float(num: i64) -> f64 {
+    return num as f64 / UNIT;
+}
+
+// checked whatever the build, see README
+fn add(a: i64, b: i64) -> i64 {
+    return a.checked_add(b).expect("position out of range");
+}
+fn sub(a: i64, b: i64) -> i64 {
+    return a.checked_sub(b).expect("position out of range");
+}
+fn mul(a: i64, b: i64) -> i64 {
+    return a.checked_mul(b).expect("position out of range");
+}
+fn neg(a: i64) -> i64 {
+    return a.checked_neg().expect("position out of range");
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+impl Position {
+    pub const ORIGIN: Position = Position{ x: 0, y: 0, z: 0 };
+
+    pub const fn new(x: i64, y: i64, z: i64) -> Position {
+        return Position{ x, y, z };
+    }
+    pub fn from_f64(pos: (f64, f64, f64)) -> Position {
+        return Position{ x: to_fixed(pos.0), y: to_fixed(pos.1), z: to_fixed(pos.2) };
+    }
+    pub fn to_f64(self) -> (f64, f64, f64) {
+        return ( to_float(self.x), to_float(self.y), to_float(self.z) );
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Displacement {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+impl Displacement {
+    pub const ZERO: Displacement = Displacement{ x: 0, y: 0, z: 0 };
+
+    pub const fn new(x: i64, y: i64, z: i64) -> Displacement {
+        return Displacement{ x, y, z };
+    }
+    pub fn from_f64(pos: (f64, f64, f64)) -> Displacement {
+        return Displacement{ x: to_fixed(pos.0), y: to_fixed(pos.1), z: to_fixed(pos.2) };
+    }
+    pub fn to_f64(self) -> (f64, f64, f64) {
+        return ( to_float(self.x), to_float(self.y), to_float(self.z) );
+    }
+
+    // metres
+    pub fn length(self) -> f64 {
+        // the sum of three squares can pass i128::MAX, hypot never overflows
+        return (self.x as f64).hypot(self.y as f64).hypot(self.z as f64) / UNIT;
+    }
+}
+
+impl ops::Sub for Position {
+    type Output = Displacement;
+    fn sub(self, rhs: Position) -> Displacement {
+        return Displacement{ x: sub(self.x, rhs.x), y: sub(self.y, rhs.y), z: sub(self.z, rhs.z) };
+    }
+}
+impl ops::Add<Displacement> for Position {
+    type Output = Position;
+    fn add(self, rhs: Displacement) -> Position {
+        return Position{ x: add(self.x, rhs.x), y: add(self.y, rhs.y), z: add(self.z, rhs.z) };
+    }
+}
+impl ops::Sub<Displacement> for Position {
+    type Output = Position;
+    fn sub(self, rhs: Displacement) -> Position {
+        return Position{ x: sub(self.x, rhs.x), y: sub(self.y, rhs.y), z: sub(self.z, rhs.z) };
+    }
+}
+impl ops::AddAssign<Displacement> for Position {
+    fn add_assign(&mut self, rhs: Displacement) {
+        *self = *self + rhs;
+    }
+}
+impl ops::SubAssign<Displacement> for Position {
+    fn sub_assign(&mut self, rhs: Displacement) {
+        *self = *self - rhs;
+    }
+}
+
+impl ops::Add for Displacement {
+    type Output = Displacement;
+    fn add(self, rhs: Displacement) -> Displacement {
+        return Displacement{ x: add(self.x, rhs.x), y: add(self.y, rhs.y), z: add(self.z, rhs.z) };
+    }
+}
+impl ops::Sub for Displacement {
+    type Output = Displacement;
+    fn sub(self, rhs: Displacement) -> Displacement {
+        return Displacement{ x: sub(self.x, rhs.x), y: sub(self.y, rhs.y), z: sub(self.z, rhs.z) };
+    }
+}
+impl ops::Neg for Displacement {
+    type Output = Displacement;
+    fn neg(self) -> Displacement {
+        return Displacement{ x: neg(self.x), y: neg(self.y), z: neg(self.z) };
+    }
+}
+impl ops::Mul<i64> for Displacement {
+    type Output = Displacement;
+    fn mul(self, rhs: i64) -> Displacement {
+        return Displacement{ x: mul(self.x, rhs), y: mul(self.y, rhs), z: mul(self.z, rhs) };
+    }
+}
+impl ops::AddAssign for Displacement {
+    fn add_assign(&mut self, rhs: Displacement) {
+        *self = *self + rhs;
+    }
+}
+impl ops::SubAssign for Displacement {
+    fn sub_assign(&mut self, rhs: Displacement) {
+        *self = *self - rhs;
+    }
+}
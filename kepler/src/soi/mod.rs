@@ -0,0 +1,113 @@
+/* README
+ * Finding when a ship moves from one sphere of influence to another, and the
+ * orbit it's on afterwards. At the crossing the ship's state is moved into the
+ * new parent's frame by adding or subtracting the body's own state, then turned
+ * back into elements with Orbit::from_state.
+ *
+ * Leaving is r = soi around the current parent, which has a closed form through
+ * the true anomaly. Entering a child's SOI depends on two orbits at once so it's
+ * searched for by conservative advancement: the gap can't close faster than both
+ * periapsis speeds together, so stepping gap/speed never jumps over a crossing.
+ */
+
+use crate::Orbit;
+use crate::angle::Angle;
+use crate::body::{BodyId, System};
+use crate::vector::*;
+
+const TOLERANCE: f64 = 1e-3;    // metres, the fixed point resolution
+const MAX_STEPS: u32 = 100000;  // grazing passes take the most
+
+// the first SOI crossing and the orbit around the new parent from then on
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transition<A: Angle = f64> {
+    pub t: f64,
+    pub orbit: Orbit<A>,
+}
+
+/* Entering
+ * First time from t_start to t_end that the ship comes within soi of body. Both
+ * orbits are around the same parent. If the ship starts inside, say because it
+ * just left, it has to get out before it counts as coming in.
+ */
+pub fn entry<A: Angle>(ship: &Orbit<A>, body: &Orbit<A>, mu: f64, soi: f64, t_start: f64, t_end: f64) -> Option<Transition<A>> {
+    let speed = max_speed(ship) + max_speed(body);
+    let gap = |t: f64| norm(sub(ship.pos_at(t), body.pos_at(t))) - soi;
+
+    let mut t = t_start;
+    let mut outside = gap(t) > TOLERANCE;
+    for _ in 0..MAX_STEPS {
+        let g = gap(t);
+        if outside && g <= TOLERANCE {
+            let (pos, vel) = ship.state_at(t);
+            let (body_pos, body_vel) = body.state_at(t);
+            let orbit = Orbit::from_state(sub(pos, body_pos), sub(vel, body_vel), mu, t);
+            return Some(Transition{ t, orbit });
+        }
+        outside |= g > TOLERANCE;
+
+        t += g.abs().max(TOLERANCE)/speed;
+        if t > t_end { return None }
+    }
+    return None;
+}
+
+/* Leaving
+ * First time from t_start that the ship gets soi away from its focus, on the way
+ * out. parent is the focus's own orbit, and the new orbit is around its focus.
+ * Starting outside and heading out counts as leaving straight away, heading in
+ * (it's just arrived) waits for the way back out.
+ */
+pub fn exit<A: Angle>(ship: &Orbit<A>, parent: &Orbit<A>, soi: f64, t_start: f64) -> Option<Transition<A>> {
+    let (pos, vel) = ship.state_at(t_start);
+    let t = if norm(pos) >= soi && dot(pos, vel) > 0.0 {
+        t_start
+    } else if ship.apoapsis() < soi {
+        return None; // apoapsis is inside
+    } else {
+        let p = ship.semi_latus_rectum();
+        let nu = ((p/soi - 1.0)/ship.e).clamp(-1.0, 1.0).acos(); // outbound, 0-pi
+        let mut t = ship.time_at_true_anomaly(nu);
+        if let Some(period) = ship.period() {
+            t += period*((t_start - t)/period).ceil(); // the next time round
+        }
+        t
+    };
+
+    let (pos, vel) = ship.state_at(t);
+    let (parent_pos, parent_vel) = parent.state_at(t);
+    let orbit = Orbit::from_state(add(pos, parent_pos), add(vel, parent_vel), parent.mu, t);
+    return Some(Transition{ t, orbit });
+}
+
+// periapsis is always the fastest point
+fn max_speed<A: Angle>(orbit: &Orbit<A>) -> f64 {
+    return orbit.speed_at_radius(orbit.q);
+}
+
+impl<A: Angle> System<A> {
+    /* Next crossing for a ship orbiting parent, into any of its children or out
+     * of its own SOI, whichever comes first. Returns the new parent as well.
+     */
+    pub fn next_transition(&self, parent: BodyId, ship: &Orbit<A>, t_start: f64, t_end: f64) -> Option<(BodyId, Transition<A>)> {
+        let mut next: Option<(BodyId, Transition<A>)> = None;
+        let mut earliest = |candidate: Option<(BodyId, Transition<A>)>| {
+            if let Some((id, transition)) = candidate {
+                if next.is_none_or(|(_, best)| transition.t < best.t) {
+                    next = Some((id, transition));
+                }
+            }
+        };
+
+        for child in self.children(parent) {
+            let body = &self[child];
+            let orbit = body.orbit.as_ref().unwrap(); // only the root has no orbit
+            earliest(entry(ship, orbit, body.mu, body.soi, t_start, t_end).map(|transition| (child, transition)));
+        }
+        if let (Some(orbit), Some(grandparent)) = (&self[parent].orbit, self[parent].parent) {
+            let leaving = exit(ship, orbit, self[parent].soi, t_start).filter(|transition| transition.t <= t_end);
+            earliest(leaving.map(|transition| (grandparent, transition)));
+        }
+        return next;
+    }
+}
@@ -0,0 +1,267 @@
+/* README
+ * Solvers for Kepler's equation M = E - e*sin(E), elliptic only. Every orbit picks
+ * one with its solver field, Newton is what Orbit::E always used.
+ * src/bin/bench.rs runs all of them over an (e, M) grid.
+ */
+
+pub mod table;
+
+use core::fmt;
+use std::f64::consts::PI;
+use std::sync::OnceLock;
+
+const PRECISION: f64 = 1e-15;   // step size to stop at, relative to E
+const MAX_ITER: u32 = 100;
+const NOISE: f64 = 4.0;         // ULPs of E that f = E - e*sin(E) - M is good to
+
+pub struct Solution {
+    pub E: f64,         // or H/D/X for the other branches
+    pub iter: u32,      // steps taken, how the solvers get compared
+    pub converged: bool,
+}
+
+/* KeplerError
+ * A solver ran out of iterations, or ended up somewhere that isn't a number.
+ * The residual is how far off Kepler's equation the last estimate is, in M.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeplerError {
+    pub iter: u32,
+    pub residual: f64,
+    pub e: f64,
+    pub M: f64,
+}
+impl fmt::Display for KeplerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "kepler solver did not converge after {} iterations (e = {}, M = {}, residual = {:e})",
+            self.iter, self.e, self.M, self.residual);
+    }
+}
+impl std::error::Error for KeplerError {}
+
+pub trait KeplerSolver {
+    fn solve(&self, e: f64, M: f64) -> Solution;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Solver {
+    #[default]
+    Newton,
+    Halley,
+    Danby,
+    Markley,
+    Fukushima,
+    Table,
+}
+impl Solver {
+    pub const ALL: [Solver; 6] = [Solver::Newton, Solver::Halley, Solver::Danby, Solver::Markley, Solver::Fukushima, Solver::Table];
+
+    pub fn get(self) -> &'static dyn KeplerSolver {
+        return match self {
+            Solver::Newton    => &Newton,
+            Solver::Halley    => &Halley,
+            Solver::Danby     => &Danby,
+            Solver::Markley   => &Markley,
+            Solver::Fukushima => &Fukushima,
+            Solver::Table     => &table::Table,
+        };
+    }
+}
+impl KeplerSolver for Solver {
+    fn solve(&self, e: f64, M: f64) -> Solution {
+        return self.get().solve(e, M);
+    }
+}
+
+// -pi-pi, the rest gets added back onto E afterwards
+fn reduce(M: f64) -> (f64, f64) {
+    let turns = 2.0*PI*(M/(2.0*PI)).round();
+    return (M - turns, turns);
+}
+/* Stopping
+ * a step under PRECISION of E, or under what rounding in f lets the step resolve.
+ * Close to e = 1 and M = 0, f' = 1 - e*cos(E) is tiny and a few ULPs of E in f
+ * turn into steps far bigger than PRECISION, which then never stop.
+ */
+pub(crate) fn done(step: f64, E: f64, f1: f64) -> bool {
+    let floor = NOISE*f64::EPSILON*E.abs()/f1.abs();
+    return step.abs() <= (PRECISION*E.abs()).max(floor);
+}
+// starting guess from Danby
+fn guess(e: f64, M: f64) -> f64 {
+    if M == 0.0 { return 0.0 } // periapsis, from anywhere else only reached in the limit
+    return M + 0.85*e*M.signum();
+}
+
+/* Newton
+ * Newton-Raphson method, but made it stable. Getting the remainder of the
+ * result is required due to the function being non-continuous.
+ * Very slowly starts to break if e=1 and M=0.
+ *
+ * max average steps: 4.87125 (e = 1, M = 0-2PI)
+ * min average steps: 0.00000 (e = 0)
+ * max steps needed:  N/A     (e = 1, M = 0)
+ * min steps needed:  0       (e = 0)
+ */
+pub struct Newton;
+impl KeplerSolver for Newton {
+    fn solve(&self, e: f64, M: f64) -> Solution {
+        const PRECISION: f64 = 9e-16;   // min stable number, relative to E
+        let (M, turns) = reduce(M);     // far from 0 a step of E can't get that small
+        let mut E: f64 = M;  // initial estimate
+
+        for i in 0..MAX_ITER {
+            let E_next = M + e*E.sin(); // calculate next guess
+            let E_diff = E_next - E;
+
+            if E_diff.abs() <= PRECISION*E.abs() { // near e = 1, M = 0 E is tiny and so is a wrong E_diff
+                return Solution{ E: E_next + turns, iter: i, converged: true };
+            } else {
+                let E_prime = 1.0 - e*E.cos();
+                E += ( E_diff/E_prime ) % 1.4; // 1.4 causes the best results
+            }
+        }
+
+        return Solution{ E: E + turns, iter: MAX_ITER, converged: false };
+    }
+}
+
+/* Halley
+ * Newton with the second derivative, cubic convergence.
+ */
+pub struct Halley;
+impl KeplerSolver for Halley {
+    fn solve(&self, e: f64, M: f64) -> Solution {
+        let (M, turns) = reduce(M);
+        let mut E = guess(e, M);
+
+        for i in 0..MAX_ITER {
+            let (s, c) = E.sin_cos();
+            let f = E - e*s - M;
+            let f1 = 1.0 - e*c;
+            let f2 = e*s;
+            let step = f/(f1 - f*f2/(2.0*f1));
+            E -= step;
+
+            if done(step, E, f1) {
+                return Solution{ E: E + turns, iter: i+1, converged: true };
+            }
+        }
+        return Solution{ E: E + turns, iter: MAX_ITER, converged: false };
+    }
+}
+
+/* Danby
+ * Danby's quartic method, third derivative as well so it usually takes 2 steps.
+ */
+pub struct Danby;
+impl KeplerSolver for Danby {
+    fn solve(&self, e: f64, M: f64) -> Solution {
+        let (M, turns) = reduce(M);
+        let mut E = guess(e, M);
+
+        for i in 0..MAX_ITER {
+            let (s, c) = E.sin_cos();
+            let f = E - e*s - M;
+            let f1 = 1.0 - e*c;
+            let f2 = e*s;
+            let f3 = e*c;
+            let d1 = -f/f1;
+            let d2 = -f/(f1 + d1*f2/2.0);
+            let d3 = -f/(f1 + d2*f2/2.0 + d2*d2*f3/6.0);
+            E += d3;
+
+            if done(d3, E, f1) {
+                return Solution{ E: E + turns, iter: i+1, converged: true };
+            }
+        }
+        return Solution{ E: E + turns, iter: MAX_ITER, converged: false };
+    }
+}
+
+/* Markley
+ * Markley (1995). Not iterative, a cubic gets close enough that a single fifth
+ * order correction lands on E. Always one step.
+ */
+pub struct Markley;
+impl KeplerSolver for Markley {
+    fn solve(&self, e: f64, M: f64) -> Solution {
+        let (M, turns) = reduce(M);
+        let sign = M.signum();
+        let M = M.abs(); // 0-pi
+
+        const PI2: f64 = PI*PI;
+        let alpha = (3.0*PI2 + 1.6*PI*(PI - M)/(1.0 + e))/(PI2 - 6.0);
+        let d = 3.0*(1.0 - e) + alpha*e;
+        let q = 2.0*alpha*d*(1.0 - e) - M*M;
+        let r = 3.0*alpha*d*(d - 1.0 + e)*M + M*M*M;
+        let w = (r.abs() + (q*q*q + r*r).sqrt()).powf(2.0/3.0);
+        let mut E = (2.0*r*w/(w*w + w*q + q*q) + M)/d;
+
+        let (s, c) = E.sin_cos();
+        let f0 = E - e*s - M;
+        let f1 = 1.0 - e*c;
+        let f2 = e*s;
+        let f3 = e*c;
+        let f4 = -f2;
+        let d3 = -f0/(f1 - f0*f2/(2.0*f1));
+        let d4 = -f0/(f1 + d3*f2/2.0 + d3*d3*f3/6.0);
+        let d5 = -f0/(f1 + d4*f2/2.0 + d4*d4*f3/6.0 + d4*d4*d4*f4/24.0);
+        E += d5;
+
+        return Solution{ E: sign*E + turns, iter: 1, converged: E.is_finite() };
+    }
+}
+
+/* Fukushima
+ * Fukushima's piecewise method. 0-pi is split into SEGMENTS pieces with sin/cos
+ * of each end in a table. The segment is found by bisecting on M, then Newton runs
+ * from the segment using the addition formulas with short series for the
+ * small offset, so the loop never calls sin/cos. E - e*sin(E) is convex over 0-pi
+ * so starting from the top of the segment Newton only ever moves down into it.
+ */
+pub struct Fukushima;
+const SEGMENTS: usize = 128;
+fn table() -> &'static [(f64, f64)] {
+    static TABLE: OnceLock<Vec<(f64, f64)>> = OnceLock::new();
+    return TABLE.get_or_init(|| {
+        (0..=SEGMENTS).map(|j| (PI*j as f64/SEGMENTS as f64).sin_cos()).collect()
+    });
+}
+impl KeplerSolver for Fukushima {
+    fn solve(&self, e: f64, M: f64) -> Solution {
+        let (M, turns) = reduce(M);
+        let sign = M.signum();
+        let M = M.abs(); // 0-pi
+        let table = table();
+        let step = PI/SEGMENTS as f64;
+
+        // M(E) is increasing so bisect the ends of the segments
+        let (mut lo, mut hi) = (0, SEGMENTS);
+        while hi - lo > 1 {
+            let mid = (lo + hi)/2;
+            if mid as f64*step - e*table[mid].0 <= M { lo = mid } else { hi = mid }
+        }
+        let (s0, c0) = table[hi];
+        let E0 = hi as f64*step;
+
+        let mut d = 0.0; // E = E0 + d, d stays within the segment
+        for i in 0..MAX_ITER {
+            let d2 = d*d;
+            let sin_d = d*(1.0 - d2/6.0*(1.0 - d2/20.0*(1.0 - d2/42.0*(1.0 - d2/72.0))));
+            let cos_d = 1.0 - d2/2.0*(1.0 - d2/12.0*(1.0 - d2/30.0*(1.0 - d2/56.0*(1.0 - d2/90.0))));
+            let s = s0*cos_d + c0*sin_d;
+            let c = c0*cos_d - s0*sin_d;
+
+            let f = E0 + d - e*s - M;
+            let f1 = 1.0 - e*c;
+            let delta = f/f1;
+            d -= delta;
+
+            if done(delta, E0, f1) { // E0 + d can't be better than ULPs of E0
+                return Solution{ E: sign*(E0 + d) + turns, iter: i+1, converged: true };
+            }
+        }
+        return Solution{ E: sign*(E0 + d) + turns, iter: MAX_ITER, converged: false };
+    }
+}
@@ -0,0 +1,133 @@
+/* Lookup table solver
+ * E(M) for one eccentricity sampled on an even grid over 0-pi, along with
+ * dE/dM = 1/(1 - e*cos(E)). Between samples it's a cubic Hermite spline, then a
+ * single Newton step cleans up what the spline missed. Negative M is mirrored.
+ * Close to e = 1, E(M) is nearly a cube root around periapsis and no cubic fits
+ * it. Segments where the spline is off by more than FIT get solved directly.
+ *
+ * A table costs about 4000 Danby solves to build, so it only pays off when a lot
+ * of solves share one e, and only whoever is doing those solves knows that.
+ * KeplerTable::shared(e) builds one or hands back the one already alive for that
+ * exact e. While anything holds it, Solver::Table uses it for that e. Every
+ * other e goes to Danby, so a catalogue where every orbit has its own e runs at
+ * Danby speed. Table and Danby can differ in the last bits, so which one an e
+ * gets is up to the caller and never to which thread asked first. Each thread
+ * remembers the last e it looked up, so runs of the same e skip the lock.
+ * A KeplerTable built with new and kept is only used through its own solve.
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::sync::atomic::{AtomicU64, Ordering};
+use super::{reduce, Danby, KeplerSolver, Solution};
+
+const SEGMENTS: usize = 1024;
+const FIT: f64 = 1e-8; // before the Newton step, which about squares it
+
+// shared tables by e, only as long as someone holds them
+fn tables() -> &'static Mutex<HashMap<u64, Weak<KeplerTable>>> {
+    static TABLES: OnceLock<Mutex<HashMap<u64, Weak<KeplerTable>>>> = OnceLock::new();
+    return TABLES.get_or_init(|| Mutex::new(HashMap::new()));
+}
+static GENERATION: AtomicU64 = AtomicU64::new(0); // bumped every time a table is shared
+
+pub struct KeplerTable {
+    pub e: f64,
+    E: Vec<f64>,
+    E_prime: Vec<f64>,
+    direct: Vec<bool>, // segments the spline doesn't fit
+}
+impl KeplerTable {
+    pub fn new(e: f64) -> KeplerTable {
+        let mut E = Vec::with_capacity(SEGMENTS+1);
+        let mut E_prime = Vec::with_capacity(SEGMENTS+1);
+        for j in 0..=SEGMENTS {
+            let M = PI*j as f64/SEGMENTS as f64;
+            let E_j = Danby.solve(e, M).E;
+            E.push(E_j);
+            E_prime.push(1.0/(1.0 - e*E_j.cos()));
+        }
+
+        let mut table = KeplerTable{ e, E, E_prime, direct: vec![false; SEGMENTS] };
+        let h = PI/SEGMENTS as f64;
+        for j in 0..SEGMENTS {
+            table.direct[j] = [0.25, 0.5, 0.75].iter().any(|t| {
+                let M = (j as f64 + t)*h;
+                return (table.spline(j, *t) - Danby.solve(e, M).E).abs() > FIT;
+            });
+        }
+        return table;
+    }
+
+    // the shared table for e, built if nobody holds one. Solver::Table uses it while it's kept
+    pub fn shared(e: f64) -> Arc<KeplerTable> {
+        if let Some(table) = tables().lock().unwrap().get(&e.to_bits()).and_then(Weak::upgrade) {
+            return table;
+        }
+        let built = Arc::new(KeplerTable::new(e)); // outside the lock, it takes a while
+
+        let mut tables = tables().lock().unwrap();
+        if let Some(table) = tables.get(&e.to_bits()).and_then(Weak::upgrade) {
+            return table; // another thread got there first
+        }
+        tables.retain(|_, table| table.strong_count() > 0);
+        tables.insert(e.to_bits(), Arc::downgrade(&built));
+        GENERATION.fetch_add(1, Ordering::Release);
+        return built;
+    }
+
+    pub fn solve(&self, M: f64) -> Solution {
+        let (M, turns) = reduce(M);
+        let sign = M.signum();
+        let M = M.abs(); // 0-pi
+
+        let h = PI/SEGMENTS as f64;
+        let j = ((M/h) as usize).min(SEGMENTS-1);
+        if self.direct[j] {
+            let solution = Danby.solve(self.e, M);
+            return Solution{ E: sign*solution.E + turns, ..solution };
+        }
+
+        let (lo, hi) = (self.E[j], self.E[j+1]); // E(M) is increasing so it's between the samples
+        let mut E = self.spline(j, M/h - j as f64).clamp(lo, hi);
+
+        let (s, c) = E.sin_cos();
+        E -= (E - self.e*s - M)/(1.0 - self.e*c); // Newton polish
+        E = E.clamp(lo, hi);
+
+        return Solution{ E: sign*E + turns, iter: 1, converged: E.is_finite() };
+    }
+
+    // cubic Hermite over segment j, t is 0-1 and the derivatives are scaled to match
+    fn spline(&self, j: usize, t: f64) -> f64 {
+        let h = PI/SEGMENTS as f64;
+        let (t2, t3) = (t*t, t*t*t);
+        return (2.0*t3 - 3.0*t2 + 1.0)*self.E[j]
+            + (t3 - 2.0*t2 + t)*h*self.E_prime[j]
+            + (-2.0*t3 + 3.0*t2)*self.E[j+1]
+            + (t3 - t2)*h*self.E_prime[j+1];
+    }
+}
+
+pub struct Table;
+impl KeplerSolver for Table {
+    fn solve(&self, e: f64, M: f64) -> Solution {
+        thread_local! {
+            static LAST: RefCell<Option<(u64, u64, Weak<KeplerTable>)>> = const { RefCell::new(None) };
+        }
+        return LAST.with(|last| {
+            let mut last = last.borrow_mut();
+            let generation = GENERATION.load(Ordering::Acquire);
+            if last.as_ref().is_none_or(|(bits, seen, _)| *bits != e.to_bits() || *seen != generation) {
+                let table = tables().lock().unwrap().get(&e.to_bits()).cloned().unwrap_or_default();
+                *last = Some((e.to_bits(), generation, table));
+            }
+            return match last.as_ref().and_then(|(_, _, table)| table.upgrade()) {
+                Some(table) => table.solve(M),
+                None => Danby.solve(e, M),
+            };
+        });
+    }
+}
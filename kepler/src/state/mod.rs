@@ -0,0 +1,72 @@
+/* README
+ * Going from a state vector back to orbital elements (rv2coe). This is what lets
+ * anything that changes velocity hand the result back to a Kepler orbit.
+ *
+ * Elements that don't exist for a given orbit are set to 0 and the angle moves
+ * to the next one along:
+ * circular:   w = 0, the anomaly is measured from the ascending node
+ * equatorial: o = 0, the node is taken as the x axis so w is the longitude of periapsis
+ * e within EPSILON of 1 is made exactly parabolic, a is too big to be useful there.
+ */
+
+use crate::{Orbit, Pos, Vel};
+use crate::angle::Angle;
+use crate::vector::*;
+
+const EPSILON: f64 = 1e-11; // relative, below this e or the node vector are treated as 0
+
+impl<A: Angle> Orbit<A> {
+    pub fn from_state(pos: Pos, vel: Vel, mu: f64, t: f64) -> Orbit<A> {
+        let r = norm(pos);
+        let v = norm(vel);
+
+        let h = cross(pos, vel);                  // angular momentum
+        let h_len = norm(h);
+        let h_hat = scale(h, 1.0/h_len);
+        let node = ( -h.1, h.0, 0.0 );            // z cross h, points at the ascending node
+        let e_vec = scale(                        // points at periapsis
+            sub(scale(pos, v*v - mu/r), scale(vel, dot(pos, vel))),
+            1.0/mu
+        );
+
+        let mut e = norm(e_vec);
+        if (e-1.0).abs() < EPSILON {
+            e = 1.0;
+        }
+        let p = h_len*h_len/mu;                   // semi-latus rectum
+        let i = (h.2/h_len).clamp(-1.0, 1.0).acos();
+
+        // equatorial, the node is anywhere so use the x axis
+        let (o, node_hat) = if norm(node) < EPSILON*h_len {
+            (0.0, (1.0, 0.0, 0.0))
+        } else {
+            (node.1.atan2(node.0), unit(node))
+        };
+        // circular, periapsis is anywhere so use the node
+        let (w, peri_hat) = if e < EPSILON {
+            e = 0.0;
+            (0.0, node_hat)
+        } else {
+            let e_hat = unit(e_vec);
+            (angle_around(node_hat, e_hat, h_hat), e_hat)
+        };
+        let nu = angle_around(peri_hat, pos, h_hat); // true anomaly
+
+        let mut orbit = Orbit::new(
+            e,
+            p/(1.0+e),
+            A::from_radians(i),
+            A::from_radians(o),
+            A::from_radians(w),
+            0.0,
+            mu
+        );
+        orbit.t0 = t - orbit.mean_from_true(nu)/orbit.mean_motion();
+        return orbit;
+    }
+}
+
+// angle from a to b, counterclockwise looking down the axis
+fn angle_around(a: Pos, b: Pos, axis: Pos) -> f64 {
+    return dot(cross(a, b), axis).atan2(dot(a, b));
+}
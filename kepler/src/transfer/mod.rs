@@ -0,0 +1,162 @@
+/* README
+ * Transfers between two coplanar elliptic orbits around the same body, going the
+ * same way round. Every burn is tangential except the last, which matches the
+ * target's velocity wherever the transfer meets it, so the target doesn't have to
+ * share apsides with the start. Only the path of the target matters, not where on
+ * it t0 puts things. That would be a rendezvous.
+ *
+ * Burns happen at an apsis of the starting orbit, whichever of the next periapsis
+ * and apoapsis is cheaper. Circular orbits have no apsides so they burn straight away.
+ *
+ * hohmann:     out to the target on the far side, half a transfer orbit later
+ * bi_elliptic: out to rb on the far side, then back to the target where it started
+ */
+
+use core::fmt;
+use std::f64::consts::PI;
+use crate::{Orbit, Pos, Vel};
+use crate::angle::Angle;
+use crate::burn::DeltaV;
+use crate::vector::*;
+
+const COPLANAR: f64 = 1e-9; // radians between the orbital planes
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Burn {
+    pub t: f64,
+    pub dv: Vel, // inertial
+}
+
+/* Transfer
+ * orbits[k] is what's flown after burns[k], the last one is on the target's path.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transfer<A: Angle = f64> {
+    pub burns: Vec<Burn>,
+    pub orbits: Vec<Orbit<A>>,
+    pub total_dv: f64,
+}
+impl<A: Angle> Transfer<A> {
+    // when the transfer ends up on the target
+    pub fn arrival(&self) -> f64 {
+        return self.burns.last().unwrap().t;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransferError {
+    NotElliptic,    // one of the orbits is parabolic or hyperbolic
+    NotCoplanar,    // different planes, or going opposite ways
+    LowApoapsis,    // a bi-elliptic rb below one of the orbits
+}
+impl fmt::Display for TransferError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            TransferError::NotElliptic => write!(f, "transfers need elliptic orbits"),
+            TransferError::NotCoplanar => write!(f, "orbits are not coplanar and going the same way"),
+            TransferError::LowApoapsis => write!(f, "bi-elliptic apoapsis is inside one of the orbits"),
+        };
+    }
+}
+impl std::error::Error for TransferError {}
+
+pub fn hohmann<A: Angle>(from: &Orbit<A>, to: &Orbit<A>, t: f64) -> Result<Transfer<A>, TransferError> {
+    check(from, to)?;
+    return Ok(cheapest(from, t, |t1| {
+        let mut transfer = Transfer{ burns: Vec::new(), orbits: Vec::new(), total_dv: 0.0 };
+        let (pos, vel) = from.state_at(t1);
+        let r2 = radius_towards(to, scale(pos, -1.0));
+        let t2 = transfer.tangential(from.mu, pos, vel, r2, t1);
+        transfer.arrive(to, t2);
+        return transfer;
+    }));
+}
+
+pub fn bi_elliptic<A: Angle>(from: &Orbit<A>, to: &Orbit<A>, rb: f64, t: f64) -> Result<Transfer<A>, TransferError> {
+    check(from, to)?;
+    if rb < from.apoapsis() || rb < to.apoapsis() {
+        return Err(TransferError::LowApoapsis);
+    }
+    return Ok(cheapest(from, t, |t1| {
+        let mut transfer = Transfer{ burns: Vec::new(), orbits: Vec::new(), total_dv: 0.0 };
+        let (pos, vel) = from.state_at(t1);
+        let tb = transfer.tangential(from.mu, pos, vel, rb, t1);
+
+        let (pos, vel) = transfer.orbits[0].state_at(tb);
+        let r2 = radius_towards(to, scale(pos, -1.0));
+        let t2 = transfer.tangential(from.mu, pos, vel, r2, tb);
+        transfer.arrive(to, t2);
+        return transfer;
+    }));
+}
+
+impl<A: Angle> Transfer<A> {
+    /* tangential burn at pos onto an orbit that reaches r on the far side.
+     * returns when it gets there, half the new orbit later.
+     */
+    fn tangential(&mut self, mu: f64, pos: Pos, vel: Vel, r: f64, t: f64) -> f64 {
+        let r1 = norm(pos);
+        let a = (r1 + r)/2.0;
+        let speed = (mu*(2.0/r1 - 1.0/a)).sqrt();                   // vis-viva
+        let direction = unit(cross(unit(cross(pos, vel)), pos));    // prograde, along the plane
+        let dv = sub(scale(direction, speed), vel);
+
+        let orbit = Orbit::from_state(pos, add(vel, dv), mu, t);
+        self.push(Burn{ t, dv }, orbit);
+        return t + PI*(a*a*a/mu).sqrt();
+    }
+    // last burn, onto the target's path where the transfer is at t
+    fn arrive(&mut self, to: &Orbit<A>, t: f64) {
+        let (pos, vel) = self.orbits.last().unwrap().state_at(t);
+        let dv = sub(vel_towards(to, pos), vel);
+        let orbit = self.orbits.last().unwrap().apply_burn(t, DeltaV::Inertial(dv));
+        self.push(Burn{ t, dv }, orbit);
+    }
+    fn push(&mut self, burn: Burn, orbit: Orbit<A>) {
+        self.total_dv += norm(burn.dv);
+        self.burns.push(burn);
+        self.orbits.push(orbit);
+    }
+}
+
+fn check<A: Angle>(from: &Orbit<A>, to: &Orbit<A>) -> Result<(), TransferError> {
+    if from.e >= 1.0 || to.e >= 1.0 {
+        return Err(TransferError::NotElliptic);
+    }
+    let h = (normal(from), normal(to));
+    if norm(cross(h.0, h.1)) > COPLANAR || dot(h.0, h.1) < 0.0 {
+        return Err(TransferError::NotCoplanar);
+    }
+    return Ok(());
+}
+
+// the same transfer from the next periapsis and apoapsis, keeps the cheaper one
+fn cheapest<A: Angle>(from: &Orbit<A>, t: f64, plan: impl Fn(f64) -> Transfer<A>) -> Transfer<A> {
+    if from.e == 0.0 {
+        return plan(t);
+    }
+    let period = from.period().unwrap();
+    let periapsis = from.t0 + period*((t - from.t0)/period).ceil();
+    let apoapsis = periapsis - period/2.0;
+    let apoapsis = if apoapsis < t {apoapsis + period} else {apoapsis};
+
+    let (a, b) = (plan(periapsis), plan(apoapsis));
+    return if b.total_dv < a.total_dv {b} else {a};
+}
+
+// unit angular momentum
+fn normal<A: Angle>(orbit: &Orbit<A>) -> Pos {
+    return orbit.orient((0.0, 0.0, 1.0));
+}
+// true anomaly of the point on the orbit in direction
+fn true_anomaly<A: Angle>(orbit: &Orbit<A>, direction: Pos) -> f64 {
+    let periapsis = orbit.orient((1.0, 0.0, 0.0));
+    return dot(cross(periapsis, direction), normal(orbit)).atan2(dot(periapsis, direction));
+}
+fn radius_towards<A: Angle>(orbit: &Orbit<A>, direction: Pos) -> f64 {
+    return norm(orbit.pos_true(true_anomaly(orbit, direction)));
+}
+// velocity on the orbit where it crosses pos's direction
+fn vel_towards<A: Angle>(orbit: &Orbit<A>, pos: Pos) -> Vel {
+    return orbit.vel_true(true_anomaly(orbit, pos));
+}
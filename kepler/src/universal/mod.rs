@@ -0,0 +1,130 @@
+/* README
+ * Universal variable propagation. One formulation for every conic, so nothing
+ * changes going across e = 1 and there is no M = E - e*sin(E) to cancel out.
+ * Everything starts from periapsis where the radial velocity is 0:
+ *
+ * sqrt(mu)*dt = (1 - alpha*q)*X^3*S(z) + q*X,   z = alpha*X^2,   alpha = 1/a = (1-e)/q
+ *
+ * X is solved with Laguerre-Conway which converges from anywhere, Newton doesn't
+ * always for elliptic orbits since the function isn't convex past apoapsis.
+ */
+
+use crate::{Orbit, Pos, Vel};
+use crate::angle::Angle;
+use crate::solver::Solution;
+use std::f64::consts::PI;
+
+const PRECISION: f64 = 1e-15;   // relative
+const MAX_ITER: u32 = 100;      // generally less than 10
+const SERIES: f64 = 1.0;        // |z| below this uses the series, cos/cosh and sin/sinh cancel
+
+/* Stumpff functions
+ * C(z) = (1 - cos(sqrt(z)))/z
+ * S(z) = (sqrt(z) - sin(sqrt(z)))/sqrt(z)^3
+ * with cosh/sinh for negative z
+ */
+pub fn stumpff_c(z: f64) -> f64 {
+    if z.abs() < SERIES {
+        return series(z, 2);
+    } else if z > 0.0 {
+        return (1.0 - z.sqrt().cos())/z;
+    }
+    return ((-z).sqrt().cosh() - 1.0)/(-z);
+}
+pub fn stumpff_s(z: f64) -> f64 {
+    if z.abs() < SERIES {
+        return series(z, 3);
+    } else if z > 0.0 {
+        let s = z.sqrt();
+        return (s - s.sin())/(s*s*s);
+    }
+    let s = (-z).sqrt();
+    return (s.sinh() - s)/(s*s*s);
+}
+
+// sum of (-z)^k/(2k+n)!, for |z| < 1 ten terms is past f64 precision
+fn series(z: f64, n: u32) -> f64 {
+    let mut term = 1.0/(1..=n).product::<u32>() as f64;
+    let mut sum = term;
+    for k in 1..10 {
+        let m = (2*k + n) as f64;
+        term *= -z/((m - 1.0)*m);
+        sum += term;
+    }
+    return sum;
+}
+
+impl<A: Angle> Orbit<A> {
+    // time since the nearest periapsis, X loses digits with every extra revolution
+    pub(crate) fn since_periapsis(&self, M: f64) -> f64 {
+        let M = if self.e < 1.0 { M - 2.0*PI*(M/(2.0*PI)).round() } else { M };
+        return M/self.mean_motion();
+    }
+    // universal anomaly dt after periapsis
+    pub(crate) fn X_at(&self, dt: f64) -> Solution {
+        return Self::X(self.q, (1.0 - self.e)/self.q, self.mu.sqrt()*dt);
+    }
+    // how far off X is, in time
+    pub(crate) fn residual_universal(&self, dt: f64, X: f64) -> f64 {
+        let alpha = (1.0 - self.e)/self.q;
+        let F = (1.0 - alpha*self.q)*X*X*X*stumpff_s(alpha*X*X) + self.q*X;
+        return F/self.mu.sqrt() - dt;
+    }
+    // state in the orbital plane dt after periapsis
+    pub(crate) fn state_universal(&self, dt: f64, X: f64) -> (Pos, Vel) {
+        let q = self.q;
+        let alpha = (1.0 - self.e)/q;
+        let sqrt_mu = self.mu.sqrt();
+        let v0 = (self.mu*(1.0 + self.e)/q).sqrt(); // periapsis speed
+
+        let z = alpha*X*X;
+        let (C, S) = (stumpff_c(z), stumpff_s(z));
+        let r = X*X*C + q*(1.0 - z*C);
+
+        // Lagrange coefficients, from (q, 0) and (0, v0)
+        let f = 1.0 - X*X*C/q;
+        let g = dt - X*X*X*S/sqrt_mu;
+        let f_dot = sqrt_mu/(r*q)*X*(z*S - 1.0);
+        let g_dot = 1.0 - X*X*C/r;
+
+        return ( (f*q, g*v0, 0.0), (f_dot*q, g_dot*v0, 0.0) );
+    }
+
+    // universal anomaly for sqrt(mu)*dt = target
+    fn X(q: f64, alpha: f64, target: f64) -> Solution {
+        const N: f64 = 5.0; // Laguerre-Conway degree
+        let k = 1.0 - alpha*q;                              // which is e
+
+        let mut X = if alpha > 0.0 {
+            target*alpha                                    // X = sqrt(a)*E and E ~ M
+        } else {
+            Self::X_cubic(q, k, target) // exact for parabolic, past the root otherwise
+        };
+
+        for i in 0..MAX_ITER {
+            let z = alpha*X*X;
+            let (C, S) = (stumpff_c(z), stumpff_s(z));
+            let F = k*X*X*X*S + q*X - target;
+            let F_prime = k*X*X*C + q;                      // = r
+            let F_prime2 = k*X*(1.0 - z*S);                 // = dr/dX
+
+            let root = ((N-1.0)*(N-1.0)*F_prime*F_prime - N*(N-1.0)*F*F_prime2).abs().sqrt();
+            let step = N*F/(F_prime + F_prime.signum()*root);
+            X -= step;
+
+            if step.abs() <= PRECISION*X.abs() {
+                return Solution{ E: X, iter: i+1, converged: true };
+            }
+        }
+        return Solution{ E: X, iter: MAX_ITER, converged: false };
+    }
+    // k*X^3/6 + q*X = target, the S = 1/6 cubic
+    fn X_cubic(q: f64, k: f64, target: f64) -> f64 {
+        if k == 0.0 { return target/q }
+        let p = 6.0*q/k;
+        let r = 3.0*target.abs()/k;
+        let d = (r*r + p*p*p/27.0).sqrt();
+        let X = (r + d).cbrt() - (d - r).cbrt();
+        return if target.is_sign_negative() {-X} else {X};
+    }
+}
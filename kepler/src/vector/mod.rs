@@ -0,0 +1,27 @@
+/* README
+ * Small helpers for the (f64, f64, f64) tuples used for positions and velocities.
+ */
+
+type Vec3 = (f64, f64, f64);
+
+pub fn add(a: Vec3, b: Vec3) -> Vec3 {
+    return ( a.0+b.0, a.1+b.1, a.2+b.2 );
+}
+pub fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    return ( a.0-b.0, a.1-b.1, a.2-b.2 );
+}
+pub fn scale(a: Vec3, s: f64) -> Vec3 {
+    return ( a.0*s, a.1*s, a.2*s );
+}
+pub fn dot(a: Vec3, b: Vec3) -> f64 {
+    return a.0*b.0 + a.1*b.1 + a.2*b.2;
+}
+pub fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    return ( a.1*b.2 - a.2*b.1, a.2*b.0 - a.0*b.2, a.0*b.1 - a.1*b.0 );
+}
+pub fn norm(a: Vec3) -> f64 {
+    return a.0.hypot(a.1).hypot(a.2);
+}
+pub fn unit(a: Vec3) -> Vec3 {
+    return scale(a, 1.0/norm(a));
+}
@@ -0,0 +1,195 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+use std::f64::consts::PI;
+use kepler::angle::{Angle, r32, r64};
+
+/* wrapping arithmetic
+ * sums past a full turn come back round exactly, however many turns it takes.
+ */
+#[test]
+fn half_turns_wrap() {
+    assert_eq!(r32::PI + r32::PI, r32::ZERO);
+    assert_eq!(r64::HALF_PI*4_u64, r64::ZERO);
+    assert_eq!(-r32::HALF_PI, r32::PI + r32::HALF_PI);
+    assert_eq!(r32::HALF_PI - r32::PI, r32::PI + r32::HALF_PI);
+    assert_eq!(r64::HALF_PI*-1_i64, -r64::HALF_PI);
+}
+
+#[test]
+fn millions_of_turns_exact() {
+    // a million and a bit revolutions in uneven steps lands exactly where it should
+    let step = r64::from_bits(0x9E3779B97F4A7C15);
+    let mut sum = r64::ZERO;
+    let mut count: u64 = 0;
+    while count < 3_000_000 {
+        sum += step;
+        count += 1;
+    }
+    assert_eq!(sum, step*count);
+    sum -= step*count;
+    assert_eq!(sum, r64::ZERO);
+}
+
+#[test]
+fn last_bit_carries_round() {
+    let mut angle = r32::from_bits(u32::MAX);
+    angle += r32::from_bits(1);
+    assert_eq!(angle, r32::ZERO);
+}
+
+#[test]
+fn ordered_by_turn() {
+    assert!(r32::HALF_PI < r32::PI && r32::PI < -r32::HALF_PI);
+}
+
+/* conversions
+ * radians and degrees in and out, and between the two widths.
+ */
+#[test]
+fn radians_to_bits() {
+    for (radians, bits) in [(0.0, 0), (PI/2.0, 1<<30), (PI, 1<<31), (-PI/2.0, 3<<30), (2.0*PI, 0), (5.0*PI, 1<<31)] {
+        assert_eq!(r32::from_radians(radians).to_bits(), bits, "{} rad", radians);
+    }
+}
+
+#[test]
+fn degrees_and_signed_radians() {
+    assert_eq!(r32::from_degrees(90.0), r32::HALF_PI);
+    assert_eq!(r64::from_degrees(-180.0), r64::PI);
+    assert!((r32::from_degrees(45.0).to_degrees() - 45.0).abs() < 1e-12);
+    assert!((r64::from_radians(-1.0).to_radians_signed() + 1.0).abs() < 1e-15);
+    assert!((r64::from_radians(-1.0).to_radians() - (2.0*PI - 1.0)).abs() < 1e-15);
+}
+
+#[test]
+fn not_finite_is_zero() {
+    for num in [f64::NAN, f64::INFINITY] {
+        assert_eq!(r32::from_radians(num), r32::ZERO, "{}", num);
+    }
+}
+
+#[test]
+fn display_in_radians() {
+    assert_eq!(format!("{:.4}", r32::PI), "3.1416");
+}
+
+#[test]
+fn widening_and_narrowing() {
+    let narrow = r32::from_bits(0xdeadbeef);
+    let wide = r64::from(narrow);
+    assert_eq!(wide.to_bits(), 0xdeadbeef_00000000);
+    assert_eq!(r32::try_from(wide), Ok(narrow));
+    assert!(r32::try_from(r64::from_bits(0xdeadbeef_00000001)).is_err());
+    assert_eq!(<r64 as Angle>::to_radians(wide), narrow.to_radians());
+}
+
+/* fixed point trig against f64
+ * below pi/4 f64 gets the argument to within about 1 ULP so the two should agree to
+ * a couple of ULP. past that f64 loses ULPs of the argument, so only the absolute
+ * difference is meaningful.
+ */
+const COUNT: u64 = 1<<20;
+const STEP: u64 = 0x9E3779B97F4A7C15; // golden ratio, hits every kind of bit pattern
+
+#[test]
+fn sin_cos_first_octant() {
+    let mut bits: u64 = 0;
+    for _ in 0..COUNT {
+        bits = bits.wrapping_add(STEP);
+        let small = r64::from_bits(bits >> 3); // 0-pi/4
+        let (s, c) = small.sin_cos();
+        let rad = small.to_radians();
+        if rad != 0.0 {
+            let ulp = ((s-rad.sin())/(rad.sin()*f64::EPSILON)).abs().max(((c-rad.cos())/(rad.cos()*f64::EPSILON)).abs());
+            assert!(ulp <= 3.0, "{:#x}: {} ULP", bits >> 3, ulp); // 0.5 ours, ~0.5 f64 sin, ~1 to_radians
+        }
+    }
+}
+
+#[test]
+fn sin_cos_full_circle() {
+    let mut bits: u64 = 0;
+    for _ in 0..COUNT {
+        bits = bits.wrapping_add(STEP);
+        let angle = r64::from_bits(bits);
+        let (s, c) = angle.sin_cos();
+        let rad = angle.to_radians();
+        let diff = (s-rad.sin()).abs().max((c-rad.cos()).abs());
+        assert!(diff <= 8.0*f64::EPSILON, "{:#x}: {:e}", bits, diff); // to_radians is off by up to 1.5 ULP of 2pi
+    }
+}
+
+/* fixed point atan2 against f64
+ * both widths, over every quadrant and a wide spread of magnitudes. r64 should
+ * agree to a couple of f64 ULP of pi and r32 to its own last bit. Infinities go
+ * along the axes the way f64 does and NaN is 0 rather than a panic.
+ */
+fn turn_diff(a: f64, b: f64) -> f64 {
+    let d = (a - b).rem_euclid(2.0*PI);
+    return d.min(2.0*PI - d);
+}
+
+#[test]
+fn atan2_against_f64() {
+    let mut bits: u64 = 0;
+    for j in 0..1<<16 {
+        bits = bits.wrapping_add(STEP);
+        let (s, c) = r64::from_bits(bits).to_radians().sin_cos();
+        let size = 2.0_f64.powi((j % 600) - 300);
+        let (y, x) = (s*size, c*size);
+        let expected = y.atan2(x);
+        let wide = turn_diff(r64::atan2(y, x).to_radians(), expected);
+        let narrow = turn_diff(r32::atan2(y, x).to_radians(), expected);
+        assert!(wide <= 4.0*f64::EPSILON, "r64 atan2({:e}, {:e}): {:e}", y, x, wide);
+        assert!(narrow <= 0.5*2.0*PI/4294967296.0 + 4.0*f64::EPSILON, "r32 atan2({:e}, {:e}): {:e}", y, x, narrow);
+    }
+}
+
+#[test]
+fn atan2_axes() {
+    assert_eq!(r32::atan2(1.0, 0.0), r32::HALF_PI);
+    assert_eq!(r64::atan2(-1.0, 0.0), -r64::HALF_PI);
+    assert_eq!(r64::atan2(5e-324, -1e308), r64::PI); // subnormal against huge
+}
+
+#[test]
+fn atan2_infinite() {
+    let inf = f64::INFINITY;
+    for (y, x) in [(inf, 1.0), (-inf, 1.0), (1.0, inf), (-1.0, inf), (1.0, -inf), (-0.0, -inf), (inf, inf), (-inf, inf), (inf, -inf), (-inf, -inf), (inf, 0.0)] {
+        assert!(turn_diff(r64::atan2(y, x).to_radians(), y.atan2(x)) <= 4.0*f64::EPSILON, "r64 atan2({}, {})", y, x);
+        assert!(turn_diff(r32::atan2(y, x).to_radians(), y.atan2(x)) <= 1e-9, "r32 atan2({}, {})", y, x);
+    }
+}
+
+#[test]
+fn atan2_nan_is_zero() {
+    for (y, x) in [(f64::NAN, 1.0), (1.0, f64::NAN), (f64::NAN, f64::INFINITY), (f64::NAN, f64::NAN)] {
+        assert_eq!(r64::atan2(y, x), r64::ZERO, "atan2({}, {})", y, x);
+        assert_eq!(r32::atan2(y, x), r32::ZERO, "atan2({}, {})", y, x);
+    }
+}
+
+/* r32 trig
+ * every r32 is exact in the widened turn, so sin/cos should match f64 the same
+ * way r64 does, and the quarter turns exactly.
+ */
+#[test]
+fn sin_cos_r32() {
+    let mut bits: u32 = 0;
+    for _ in 0..1<<18 {
+        bits = bits.wrapping_add(0x9E3779B9);
+        let angle = r32::from_bits(bits);
+        let (s, c) = angle.sin_cos();
+        let rad = angle.to_radians();
+        let diff = (s - rad.sin()).abs().max((c - rad.cos()).abs());
+        assert!(diff <= 8.0*f64::EPSILON, "{:#x}: {:e}", bits, diff);
+        assert_eq!((angle.sin(), angle.cos()), (s, c), "{:#x}", bits);
+    }
+}
+
+#[test]
+fn quarter_turns_exact() {
+    assert_eq!(r32::HALF_PI.sin_cos(), (1.0, 0.0));
+    assert_eq!(r32::PI.sin_cos(), (0.0, -1.0));
+}
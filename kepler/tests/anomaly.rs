@@ -0,0 +1,75 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use kepler::*;
+use common::*;
+
+/* anomaly conversions
+ * every way round between mean, eccentric and true for each kind of conic, and
+ * pos_true/time_at_true_anomaly have to land where pos and pos_at do.
+ */
+const ECCENTRICITIES: [f64; 8] = [0.0, 0.3, 0.9, 0.999, 1.0, 1.001, 1.5, 5.0];
+const MEAN: [f64; 8] = [-20.0, -3.0, -0.5, 0.0, 1e-6, 0.7, 2.5, 9.0];
+
+fn orbit(e: f64) -> Orbit {
+    return Orbit{ i: 0.4, t0: 100.0, ..leo(e) };
+}
+
+#[test]
+fn true_from_mean_goes_through_eccentric() {
+    for e in ECCENTRICITIES {
+        let orbit = orbit(e);
+        for M in MEAN {
+            let nu = orbit.true_from_eccentric(orbit.eccentric_from_mean(M));
+            assert!((orbit.true_from_mean(M) - nu).abs() <= 1e-12*nu.abs().max(1.0), "e = {}, M = {}", e, M);
+        }
+    }
+}
+
+#[test]
+fn conversions_round_trip() {
+    for e in ECCENTRICITIES {
+        let orbit = orbit(e);
+        for M in MEAN {
+            let E = orbit.eccentric_from_mean(M);
+            let nu = orbit.true_from_eccentric(E);
+            let mean = (orbit.mean_from_eccentric(E) - M).abs()/M.abs().max(1.0);
+            let eccentric = (orbit.eccentric_from_true(nu) - E).abs()/E.abs().max(1.0);
+            let through_true = (orbit.mean_from_true(nu) - M).abs()/M.abs().max(1.0);
+            assert!(mean < 1e-9, "e = {}, M = {}: mean from eccentric {:e}", e, M, mean);
+            assert!(eccentric < 1e-9, "e = {}, M = {}: eccentric from true {:e}", e, M, eccentric);
+            assert!(through_true < 1e-9, "e = {}, M = {}: mean from true {:e}", e, M, through_true);
+        }
+    }
+}
+
+#[test]
+fn state_at_true_anomaly() {
+    for e in ECCENTRICITIES {
+        let orbit = orbit(e);
+        for M in MEAN {
+            let nu = orbit.true_from_mean(M);
+            let pos = gap(orbit.pos_true(nu), orbit.pos(M));
+            let vel = gap(orbit.vel_true(nu), orbit.vel(M));
+            assert!(pos < 1e-9 && vel < 1e-9, "e = {}, M = {}: position {:e}, velocity {:e}", e, M, pos, vel);
+        }
+    }
+}
+
+#[test]
+fn time_at_true_anomaly() {
+    for e in ECCENTRICITIES {
+        let orbit = orbit(e);
+        for M in MEAN {
+            let t = orbit.time_at_true_anomaly(orbit.true_from_mean(M));
+            let err = gap(orbit.pos_at(t), orbit.pos(M));
+            assert!(err < 1e-9, "e = {}, M = {}: {:e}", e, M, err);
+            if e < 1.0 {
+                let revolution = ((t - orbit.t0)*orbit.mean_motion() - M).abs();
+                assert!(revolution < 1e-9*M.abs().max(1.0), "e = {}, M = {}: a different revolution", e, M);
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use std::f64::consts::PI;
+use kepler::*;
+use kepler::batch::OrbitSet;
+use kepler::solver::Solver;
+use common::*;
+
+/* batch positions
+ * pos_many and OrbitSet against pos_at with Danby, which is what the batch loop
+ * runs a fixed number of steps of. Orbits the batch doesn't take go through
+ * pos_at so they have to match exactly and stay in order.
+ */
+fn orbits() -> Vec<Orbit> {
+    let mut orbits = Vec::new();
+    for e in [0.0, 0.1, 0.5, 0.9, 0.99, 0.9999, 0.999999, 1.0, 2.0] {
+        for i in [0.0, 0.3, PI] {
+            orbits.push(Orbit{ i, t0: 100.0, solver: Solver::Danby, ..leo(e) });
+        }
+    }
+    return orbits;
+}
+fn times() -> Vec<f64> {
+    return (0..10000).map(|j| 7.3*j as f64 - 3.0e4).collect();
+}
+fn check(pos: Pos, orbit: &Orbit, t: f64) {
+    let diff = vector::norm(vector::sub(pos, orbit.pos_at(t)));
+    if orbit.e < 1.0 {
+        assert!(diff/orbit.a < 1e-14, "e = {}, i = {}, t = {}: {:e} a", orbit.e, orbit.i, t, diff/orbit.a);
+    } else {
+        assert_eq!(diff, 0.0, "e = {}, i = {}, t = {}", orbit.e, orbit.i, t);
+    }
+}
+
+#[test]
+fn pos_many_matches_pos_at() {
+    let ts = times();
+    for orbit in &orbits() {
+        let many = orbit.pos_many(&ts);
+        assert_eq!(many.len(), ts.len());
+        for (pos, &t) in many.iter().zip(&ts) {
+            check(*pos, orbit, t);
+        }
+    }
+}
+
+#[test]
+fn orbit_set_matches_pos_at() {
+    let orbits = orbits();
+    let set: OrbitSet = orbits.iter().copied().collect();
+    assert_eq!(set.len(), orbits.len());
+    for &t in &times()[..100] {
+        for (pos, orbit) in set.pos_at(t).iter().zip(&orbits) {
+            check(*pos, orbit, t);
+        }
+    }
+}
+
+#[test]
+fn orbit_set_state_matches_state_at() {
+    let orbits = orbits();
+    let set: OrbitSet = orbits.iter().copied().collect();
+    for &t in &times()[..100] {
+        let (pos, state) = (set.pos_at(t), set.state_at(t));
+        for ((pos, (state_pos, vel)), orbit) in pos.iter().zip(&state).zip(&orbits) {
+            assert_eq!(pos, state_pos, "e = {}, i = {}, t = {}", orbit.e, orbit.i, t);
+            let err = vector::norm(vector::sub(*vel, orbit.vel_at(t)))/vector::norm(orbit.vel_at(t));
+            assert!(err < 1e-13, "e = {}, i = {}, t = {}: {:e}", orbit.e, orbit.i, t, err);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+use kepler::*;
+use kepler::body::{System, BodyId, SystemError};
+use kepler::position::Displacement;
+
+/* body tree
+ * sun, earth, moon. the moon's absolute position is the earth's plus its own
+ * orbit, both ways of getting it have to agree to the millimetre.
+ */
+const SUN: f64 = 1.32712440018e20;
+const EARTH: f64 = 3.986004418e14;
+const MOON: f64 = 4.9048695e12;
+const TIMES: [f64; 4] = [0.0, 1.0e6, 3.15e7, -5.0e8];
+
+fn system() -> (System, BodyId, BodyId) {
+    let mut system = System::new("sun", SUN, 6.957e8);
+    let earth = system.add(system.root(), "earth", EARTH, 6.371e6, Orbit::new(0.0167, 1.471e11, 0.0, 0.0, 1.796, 0.0, SUN)).unwrap();
+    let moon = system.add(earth, "moon", MOON, 1.737e6, Orbit::new(0.0549, 3.633e8, 0.0898, 2.18, 5.55, 1.0e5, EARTH)).unwrap();
+    return (system, earth, moon);
+}
+
+#[test]
+fn soi_radii() {
+    let (system, earth, moon) = system();
+    assert!((system[earth].soi - 9.25e8).abs() < 0.01e8, "earth {:e} m", system[earth].soi);
+    assert!((system[moon].soi - 6.6e7).abs() < 0.1e7, "moon {:e} m", system[moon].soi);
+}
+
+#[test]
+fn tree_lookups() {
+    let (system, earth, moon) = system();
+    assert_eq!(system.find("moon"), Some(moon));
+    assert_eq!(system.children(system.root()).collect::<Vec<_>>(), vec![earth]);
+    assert_eq!(system.ancestors(moon).collect::<Vec<_>>(), vec![moon, earth, system.root()]);
+}
+
+#[test]
+fn orbit_has_to_be_around_the_parent() {
+    let (mut system, earth, _) = system();
+    let around_sun = Orbit::new(0.0, 3.844e8, 0.0, 0.0, 0.0, 0.0, SUN);
+    assert_eq!(system.add(earth, "moonlet", 1.0e6, 1.0e3, around_sun), Err(SystemError::Mu));
+    assert_eq!(system.add_with_soi(earth, "moonlet", 1.0e6, 1.0e3, 1.0e5, around_sun), Err(SystemError::Mu));
+    assert_eq!(system.len(), 3);
+}
+
+#[test]
+fn positions_add_up_the_tree() {
+    let (system, earth, moon) = system();
+    let moon_orbit = system[moon].orbit.unwrap();
+    for t in TIMES {
+        let relative = Displacement::from_f64(moon_orbit.pos_at(t));
+        assert_eq!(system.pos_at(moon, t), system.pos_at(earth, t) + relative, "t = {}", t);
+        assert_eq!(system.displacement(earth, moon, t), relative, "t = {}", t);
+
+        let distance = (system.pos_at(moon, t) - system.pos_at(system.root(), t)).length();
+        assert!(distance > 1.46e11 && distance < 1.53e11, "t = {}: {:e} m from the sun", t, distance);
+    }
+}
+
+#[test]
+fn velocities_add_up_the_tree() {
+    let (system, earth, moon) = system();
+    for t in TIMES {
+        let expected = vector::add(system[earth].orbit.unwrap().vel_at(t), system[moon].orbit.unwrap().vel_at(t));
+        assert_eq!(system.vel_at(moon, t), expected, "t = {}", t);
+    }
+}
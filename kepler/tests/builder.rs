@@ -0,0 +1,108 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use std::f64::consts::PI;
+use kepler::*;
+use kepler::angle::{r32, r64};
+use kepler::builder::{OrbitBuilder, OrbitError};
+use common::*;
+
+/* builder
+ * every way of giving the size lands on the same orbit, angles come out in range
+ * without moving anything, and bad input gets the right error.
+ */
+fn expected() -> Orbit {
+    return Orbit::new(0.25, PERIAPSIS, 0.4, 1.0, 2.0, 100.0, MU);
+}
+fn base() -> OrbitBuilder<f64> {
+    return Orbit::<f64>::builder(MU).i(0.4).o(1.0).w(2.0).t0(100.0);
+}
+
+#[test]
+fn every_size_same_orbit() {
+    let a = PERIAPSIS/0.75;
+    let T = expected().period().unwrap();
+    for (name, orbit) in [
+        ("periapsis", base().e(0.25).periapsis(PERIAPSIS).build().unwrap()),
+        ("semi-major axis", base().e(0.25).semi_major_axis(a).build().unwrap()),
+        ("apsides", base().apsides(PERIAPSIS, a*1.25).build().unwrap()),
+        ("period", base().e(0.25).period(T).build().unwrap()),
+    ] {
+        let diff = vector::norm(vector::sub(orbit.pos_at(5000.0), expected().pos_at(5000.0)));
+        assert!(diff < 1e-6, "{}: {} m off", name, diff);
+    }
+}
+
+#[test]
+fn hyperbolic_semi_major_axis() {
+    let hyperbolic = Orbit::<f64>::builder(MU).e(1.5).semi_major_axis(-2.0e7).build().unwrap();
+    assert_eq!((hyperbolic.q, hyperbolic.a), (1.0e7, -2.0e7));
+}
+
+#[test]
+fn epoch_from_mean_anomaly() {
+    for e in [0.25, 1.0, 3.0] {
+        let orbit = Orbit::<f64>::builder(MU).e(e).periapsis(PERIAPSIS).mean_anomaly(4.0, 1000.0).build().unwrap();
+        let diff = vector::norm(vector::sub(orbit.pos_at(1000.0), orbit.pos(4.0)));
+        assert!(diff < 1e-6, "e = {}: {} m off", e, diff);
+    }
+}
+
+#[test]
+fn angles_normalised_in_place() {
+    for (i, o, w) in [(-0.3, 1.0, 2.0), (4.0, -1.0, 9.0), (2.0*PI + 0.1, 7.0, -20.0)] {
+        let raw = Orbit::new(0.25, PERIAPSIS, i, o, w, 0.0, MU);
+        let orbit = Orbit::<f64>::builder(MU).e(0.25).periapsis(PERIAPSIS).i(i).o(o).w(w).build().unwrap();
+        assert!((0.0..=PI).contains(&orbit.i) && (0.0..2.0*PI).contains(&orbit.o) && (0.0..2.0*PI).contains(&orbit.w),
+            "({}, {}, {}) -> ({}, {}, {})", i, o, w, orbit.i, orbit.o, orbit.w);
+        for t in [0.0, 1234.0, 4000.0] {
+            let (p, v) = (orbit.state_at(t), raw.state_at(t));
+            let (pos, vel) = (vector::norm(vector::sub(p.0, v.0)), vector::norm(vector::sub(p.1, v.1)));
+            assert!(pos < 1e-6 && vel < 1e-9, "({}, {}, {}), t = {}: {:e} m, {:e} m/s", i, o, w, t, pos, vel);
+        }
+    }
+}
+
+#[test]
+fn r32_inclination_folded() {
+    let orbit = Orbit::<r32>::builder(MU).e(0.1).periapsis(PERIAPSIS).i(r32::from_radians(5.0)).build().unwrap();
+    assert!((orbit.i.to_radians() - (2.0*PI - 5.0)).abs() < 1e-6, "i = {}", orbit.i);
+}
+
+#[test]
+fn r64_keeps_every_bit() {
+    // and the flip past pi is exact in fixed point
+    let (i, o, w) = (r64::from_bits(0x1000000000000001), r64::from_bits(0x0123456789abcdef), r64::from_bits(0xfedcba9876543210));
+    let orbit = Orbit::<r64>::builder(MU).e(0.1).periapsis(PERIAPSIS).i(i).o(o).w(w).build().unwrap();
+    assert_eq!((orbit.i, orbit.o, orbit.w), (i, o, w));
+    let past = r64::from_bits(0x8000000000000001);
+    let orbit = Orbit::<r64>::builder(MU).e(0.1).periapsis(PERIAPSIS).i(past).o(o).w(w).build().unwrap();
+    assert_eq!((orbit.i, orbit.o, orbit.w), (r64::from_bits(0x7fffffffffffffff), o + r64::PI, w + r64::PI));
+    let raw = Orbit::new(0.1, PERIAPSIS, past, o, w, 0.0, MU);
+    let diff = vector::norm(vector::sub(orbit.pos_at(1234.0), raw.pos_at(1234.0)));
+    assert!(diff < 1e-6, "{} m off", diff);
+}
+
+#[test]
+fn errors() {
+    let T = expected().period().unwrap();
+    let errors: [(Result<Orbit, OrbitError>, OrbitError); 12] = [
+        (Orbit::builder(MU).e(0.1).build(), OrbitError::NoSize),
+        (Orbit::builder(MU).e(f64::NAN).periapsis(7e6).build(), OrbitError::NotFinite),
+        (Orbit::builder(MU).periapsis(7e6).i(f64::INFINITY).build(), OrbitError::NotFinite),
+        (Orbit::builder(-MU).periapsis(7e6).build(), OrbitError::Mu),
+        (Orbit::builder(MU).e(-0.1).periapsis(7e6).build(), OrbitError::Eccentricity),
+        (Orbit::builder(MU).periapsis(-7e6).build(), OrbitError::Size),
+        (Orbit::builder(MU).apsides(8e6, 7e6).build(), OrbitError::Size),
+        (Orbit::builder(MU).e(1.5).semi_major_axis(7e6).build(), OrbitError::Eccentricity),
+        (Orbit::builder(MU).e(1.0).semi_major_axis(7e6).build(), OrbitError::Eccentricity),
+        (Orbit::builder(MU).e(1.2).period(T).build(), OrbitError::Eccentricity),
+        (Orbit::builder(MU).periapsis(7e6).period(T).build(), OrbitError::Overdetermined),
+        (Orbit::builder(MU).periapsis(7e6).t0(0.0).mean_anomaly(1.0, 0.0).build(), OrbitError::Overdetermined),
+    ];
+    for (j, (result, error)) in errors.into_iter().enumerate() {
+        assert_eq!(result, Err(error), "case {}", j);
+    }
+}
@@ -0,0 +1,121 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use kepler::*;
+use kepler::burn::DeltaV;
+use common::*;
+
+/* impulsive burns
+ * from a circular orbit up through e = 1 and back down again. the position can't
+ * move and the velocity has to change by exactly dv, whatever e ends up as.
+ */
+fn speed() -> f64 {
+    return (MU/PERIAPSIS).sqrt();
+}
+fn escape() -> f64 {
+    return (2.0_f64.sqrt() - 1.0)*speed();
+}
+// time, prograde, normal, radial
+fn burns() -> [(f64, f64, f64, f64); 5] {
+    return [
+        (100.0, 500.0, 0.0, 0.0),                   // elliptic
+        (250.0, escape(), 0.0, 0.0),                // parabolic
+        (400.0, escape() + 1000.0, 0.0, 0.0),       // hyperbolic
+        (900.0, 0.0, 800.0, 0.0),                   // plane change
+        (1300.0, 0.0, 0.0, 300.0),                  // radial
+    ];
+}
+fn local(prograde: f64, normal: f64, radial: f64) -> DeltaV {
+    return DeltaV::Local{ prograde, normal, radial };
+}
+// same place, velocity changed by dv
+fn check(before: &Orbit, after: &Orbit, t: f64, dv: Vel) {
+    let pos = vector::norm(vector::sub(after.pos_at(t), before.pos_at(t)));
+    let vel = vector::norm(vector::sub(after.vel_at(t), vector::add(before.vel_at(t), dv)));
+    assert!(pos < 1e-3, "e {} -> {}, t = {}: moved {:e} m", before.e, after.e, t, pos);
+    assert!(vel < 1e-6, "e {} -> {}, t = {}: dv off by {:e} m/s", before.e, after.e, t, vel);
+}
+
+#[test]
+fn local_burns_keep_position() {
+    let orbit = leo(0.0);
+    for (t, prograde, normal, radial) in burns() {
+        let after = orbit.apply_burn(t, local(prograde, normal, radial));
+        check(&orbit, &after, t, vector::sub(after.vel_at(t), orbit.vel_at(t)));
+        let dv = vector::norm(vector::sub(after.vel_at(t), orbit.vel_at(t)));
+        let expected = (prograde*prograde + normal*normal + radial*radial).sqrt();
+        assert!((dv - expected).abs() < 1e-9, "t = {}: {} m/s, expected {}", t, dv, expected);
+    }
+}
+
+#[test]
+fn inertial_same_as_local() {
+    let orbit = leo(0.0);
+    for (t, prograde, normal, radial) in burns() {
+        let after = orbit.apply_burn(t, local(prograde, normal, radial));
+        let inertial = vector::sub(after.vel_at(t), orbit.vel_at(t));
+        let same = orbit.apply_burn(t, DeltaV::Inertial(inertial));
+        check(&orbit, &same, t, inertial);
+        let diff = vector::norm(vector::sub(same.pos_at(t + 1000.0), after.pos_at(t + 1000.0)));
+        assert!(diff < 1e-3, "t = {}: {:e} m apart", t, diff);
+    }
+}
+
+#[test]
+fn prograde_through_escape() {
+    let orbit = leo(0.0);
+    let e = |dv: f64| orbit.apply_burn(0.0, local(dv, 0.0, 0.0)).e;
+    assert!(e(500.0) < 1.0);
+    assert_eq!(e(escape()), 1.0);
+    assert!(e(escape() + 1000.0) > 1.0);
+}
+
+#[test]
+fn plane_change_tilts_by_dv() {
+    let orbit = leo(0.0);
+    let t = 900.0;
+    let tilted = orbit.apply_burn(t, local(0.0, 800.0, 0.0));
+    let h = |orbit: &Orbit| vector::unit(vector::cross(orbit.pos_at(t), orbit.vel_at(t)));
+    let angle = vector::dot(h(&orbit), h(&tilted)).acos();
+    assert!((angle - (800.0/speed()).atan()).abs() < 1e-9, "{} rad", angle);
+}
+
+#[test]
+fn back_down_from_open_orbits() {
+    // at and away from periapsis
+    let orbit = leo(0.0);
+    for (t, prograde) in [(250.0, escape()), (400.0, escape() + 1000.0)] {
+        let before = orbit.apply_burn(t, local(prograde, 0.0, 0.0));
+        for dt in [0.0, 500.0] {
+            let v = vector::norm(before.vel_at(t + dt));
+            let after = before.apply_burn(t + dt, local(-0.2*v, 0.0, 0.0));
+            check(&before, &after, t + dt, vector::sub(after.vel_at(t + dt), before.vel_at(t + dt)));
+            assert!(after.e < 1.0, "e {} -> {}", before.e, after.e);
+            assert_eq!(after.mu, before.mu);
+        }
+    }
+}
+
+#[test]
+fn just_either_side_of_escape() {
+    // a few mm/s either way is within 1e-5 of e = 1, where E/H would cancel away.
+    // it has to stay next to the parabola and keep the energy the burn gave it
+    let orbit = leo(0.0);
+    let parabola = orbit.apply_burn(0.0, local(escape(), 0.0, 0.0));
+    for off in [-3e-3, -1e-3, 1e-3, 3e-3] {
+        let after = orbit.apply_burn(0.0, local(escape() + off, 0.0, 0.0));
+        assert_eq!((after.e - 1.0).signum(), off.signum(), "{} mm/s: e = {}", off*1e3, after.e);
+        check(&orbit, &after, 0.0, vector::sub(after.vel_at(0.0), orbit.vel_at(0.0)));
+        for i in 0..=30 {
+            let t = 100.0*i as f64;
+            let (pos, vel) = after.try_state_at(t).unwrap_or_else(|err| panic!("{} mm/s, t = {}: {}", off*1e3, t, err));
+            let err = gap(pos, parabola.pos_at(t));
+            assert!(err < (after.e - 1.0).abs(), "{} mm/s, t = {}: {:e} from the parabola", off*1e3, t, err);
+            let energy = vector::dot(vel, vel)/2.0 - MU/vector::norm(pos);
+            let drift = (energy + MU/(2.0*after.a)).abs()/(MU/PERIAPSIS);
+            assert!(drift < 1e-12, "{} mm/s, t = {}: energy off by {:e}", off*1e3, t, drift);
+        }
+    }
+}
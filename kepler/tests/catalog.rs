@@ -0,0 +1,74 @@
+#![cfg(feature = "parallel")]
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use kepler::*;
+use kepler::batch::OrbitSet;
+use kepler::catalog::OrbitCatalog;
+use kepler::solver::Solver;
+use kepler::solver::table::KeplerTable;
+use common::*;
+
+/* parallel catalog
+ * the same positions and states as an OrbitSet on one thread, bit for bit, with
+ * 1, 2 and 8 threads, and the same positions from pos_at and state_at.
+ * needs --features parallel
+ */
+const COUNT: usize = 10000;
+const TIMES: [f64; 3] = [0.0, 1234.5, -1.0e6];
+fn orbits() -> Vec<Orbit> {
+    return (0..COUNT).map(|j| Orbit::new(1.5*j as f64/COUNT as f64, PERIAPSIS + j as f64, 0.3, 1.0, 2.0, j as f64, MU)).collect();
+}
+fn on_threads<T: Send>(threads: usize, f: impl FnOnce() -> T + Send) -> T {
+    return rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap().install(f);
+}
+
+#[test]
+fn pos_at_same_as_orbit_set() {
+    let orbits = orbits();
+    let catalog: OrbitCatalog = orbits.iter().copied().collect();
+    for t in TIMES {
+        let mut expected = Vec::new();
+        for chunk in orbits.chunks(1024) {
+            expected.extend(chunk.iter().copied().collect::<OrbitSet>().pos_at(t));
+        }
+        for threads in [1, 2, 8] {
+            assert!(on_threads(threads, || catalog.pos_at(t)) == expected, "t = {}, {} threads", t, threads);
+        }
+    }
+}
+
+#[test]
+fn state_at_same_as_orbit_set() {
+    let orbits = orbits();
+    let catalog: OrbitCatalog = orbits.iter().copied().collect();
+    for t in TIMES {
+        let mut expected = Vec::new();
+        for chunk in orbits.chunks(1024) {
+            expected.extend(chunk.iter().copied().collect::<OrbitSet>().state_at(t));
+        }
+        for threads in [1, 2, 8] {
+            assert!(on_threads(threads, || catalog.state_at(t)) == expected, "t = {}, {} threads", t, threads);
+        }
+    }
+}
+
+#[test]
+fn pos_at_same_as_state_at_whatever_the_solver() {
+    // Table against Danby is where the last bits differ, Orbit::pos_at would show it
+    let es = [0.1, 0.5, 0.9];
+    let _tables: Vec<_> = es.iter().map(|&e| KeplerTable::shared(e)).collect();
+    let catalog: OrbitCatalog = (0..COUNT)
+        .map(|j| Orbit{ t0: j as f64, solver: Solver::Table, ..leo(es[j % es.len()]) })
+        .collect();
+    for t in TIMES {
+        for threads in [1, 2, 8] {
+            let (pos, state) = on_threads(threads, || (catalog.pos_at(t), catalog.state_at(t)));
+            for (k, (pos, (state_pos, _))) in pos.iter().zip(&state).enumerate() {
+                assert_eq!(pos, state_pos, "t = {}, {} threads, orbit {}", t, threads, k);
+            }
+        }
+    }
+}
@@ -0,0 +1,20 @@
+/* README
+ * What the integration tests share. Most of them fly the same orbit, 7000 km
+ * periapsis around the earth, tilted and turned so no element lines up with an
+ * axis. Each test file only uses some of this.
+ */
+#![allow(dead_code)]
+
+use kepler::*;
+
+pub const MU: f64 = 3.986e14;
+pub const PERIAPSIS: f64 = 7.0e6;
+
+// eccentricity e with periapsis at t = 0, see README
+pub fn leo(e: f64) -> Orbit {
+    return Orbit::new(e, PERIAPSIS, 0.3, 1.0, 2.0, 0.0, MU);
+}
+// how far a is from b, relative to the size of b
+pub fn gap(a: Pos, b: Pos) -> f64 {
+    return vector::norm(vector::sub(a, b))/vector::norm(b);
+}
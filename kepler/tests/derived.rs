@@ -0,0 +1,74 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use std::f64::consts::PI;
+use kepler::*;
+use common::*;
+
+/* derived quantities
+ * against what the state says at a few points on each kind of conic.
+ */
+const ECCENTRICITIES: [f64; 6] = [0.0, 0.3, 0.9, 1.0, 1.5, 5.0];
+
+fn orbit(e: f64) -> Orbit {
+    return Orbit{ i: 0.4, t0: 100.0, ..leo(e) };
+}
+fn relative(a: f64, b: f64) -> f64 {
+    return (a - b).abs()/b.abs().max(1e-300);
+}
+
+#[test]
+fn periapsis_and_semi_latus_rectum() {
+    for e in ECCENTRICITIES {
+        let orbit = orbit(e);
+        let q = relative(orbit.periapsis(), vector::norm(orbit.pos(0.0)));
+        let p = relative(orbit.semi_latus_rectum(), vector::norm(orbit.pos_true(PI/2.0)));
+        assert!(q < 1e-9 && p < 1e-9, "e = {}: periapsis {:e}, semi-latus rectum {:e}", e, q, p);
+    }
+}
+
+#[test]
+fn closed_orbits() {
+    for e in [0.0, 0.3, 0.9] {
+        let orbit = orbit(e);
+        let Q = relative(orbit.apoapsis(), vector::norm(orbit.pos(PI)));
+        let T = orbit.period().unwrap();
+        let repeat = vector::norm(vector::sub(orbit.pos_at(1234.0 + T), orbit.pos_at(1234.0)))/orbit.a;
+        assert!(Q < 1e-9 && repeat < 1e-9, "e = {}: apoapsis {:e}, a period later {:e} a", e, Q, repeat);
+        assert!(orbit.excess_speed().is_none() && orbit.asymptote_angle().is_none(), "e = {}", e);
+    }
+}
+
+#[test]
+fn open_orbits() {
+    for e in [1.0, 1.5, 5.0] {
+        let orbit = orbit(e);
+        assert!(orbit.apoapsis() == f64::INFINITY && orbit.period().is_none(), "e = {}", e);
+        let nu = orbit.asymptote_angle().unwrap();
+        assert!((1.0 + e*nu.cos()).abs() < 1e-15, "e = {}: asymptote at {}", e, nu);
+        let late = orbit.true_from_mean(1e9);
+        assert!(late < nu && nu - late < 1e-2, "e = {}: {} long after, asymptote {}", e, late, nu);
+        let far = orbit.speed_at_radius(1e30); // parabolic is still going 3e-8 m/s there
+        let err = (orbit.excess_speed().unwrap() - far).abs()/orbit.speed_at_radius(orbit.q);
+        assert!(err < 1e-9, "e = {}: excess speed {:e}", e, err);
+    }
+}
+
+#[test]
+fn against_the_state() {
+    for e in ECCENTRICITIES {
+        let orbit = orbit(e);
+        for t in [-5000.0, 0.0, 100.0, 3000.0] {
+            let (pos, vel) = orbit.state_at(t);
+            let (r, v) = (vector::norm(pos), vector::norm(vel));
+            let h = gap(orbit.angular_momentum(), vector::cross(pos, vel));
+            let energy = (orbit.specific_energy() - (v*v/2.0 - MU/r)).abs()/(MU/r);
+            let speed = relative(orbit.speed_at_radius(r), v);
+            assert!(h < 1e-9, "e = {}, t = {}: angular momentum {:e}", e, t, h);
+            assert!(energy < 1e-9, "e = {}, t = {}: energy {:e}", e, t, energy);
+            assert!(speed < 1e-9, "e = {}, t = {}: vis-viva {:e}", e, t, speed);
+        }
+    }
+}
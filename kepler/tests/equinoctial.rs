@@ -0,0 +1,140 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use std::f64::consts::PI;
+use kepler::*;
+use kepler::equinoctial::*;
+use common::*;
+
+/* equinoctial elements
+ * both sets against Orbit on every kind of conic, including the circular and
+ * equatorial ones classical elements can't describe, and a nearly circular
+ * equatorial orbit has to come out next to the exactly circular one.
+ */
+const TIMES: [f64; 4] = [1000.0, 1500.0, -3000.0, 20000.0];
+
+fn orbits() -> [Orbit; 7] {
+    return [
+        Orbit::new(0.0, 7.0e6, 0.0, 0.0, 0.0, 100.0, MU),
+        Orbit::new(1e-13, 7.0e6, 1e-13, 1.0, 2.0, 100.0, MU),
+        Orbit::new(0.01, 7.0e6, 0.9, 4.0, 0.3, -500.0, MU),
+        Orbit::new(0.7, 7.0e6, 2.5, 1.0, 5.0, 100.0, MU),
+        Orbit::new(0.999, 7.0e6, 0.4, 6.0, 2.0, 100.0, MU),
+        Orbit::new(1.0, 7.0e6, 0.4, 1.0, 2.0, 100.0, MU),
+        Orbit::new(3.0, 7.0e6, 1.2, 1.0, 2.0, 100.0, MU),
+    ];
+}
+fn state_gap(a: (Pos, Vel), b: (Pos, Vel)) -> f64 {
+    return gap(a.0, b.0) + gap(a.1, b.1);
+}
+
+#[test]
+fn modified_flies_like_orbit() {
+    for orbit in orbits() {
+        let modified = ModifiedEquinoctialElements::from_orbit(&orbit, 1000.0).unwrap();
+        let (pos, vel) = orbit.state_at(1000.0);
+        let from_state = ModifiedEquinoctialElements::from_state(pos, vel, MU, 1000.0).unwrap();
+        for t in TIMES {
+            let expected = orbit.state_at(t);
+            for (name, state) in [("from_orbit", modified.state_at(t)), ("from_state", from_state.state_at(t)), ("to_orbit", modified.to_orbit::<f64>().state_at(t))] {
+                let err = state_gap(state, expected);
+                assert!(err < 1e-9, "e = {}, t = {}, {}: {:e}", orbit.e, t, name, err);
+            }
+        }
+    }
+}
+
+#[test]
+fn equinoctial_flies_like_orbit() {
+    for orbit in orbits().into_iter().filter(|orbit| orbit.e < 1.0) {
+        let elements = EquinoctialElements::from_orbit(&orbit, 1000.0).unwrap();
+        for t in TIMES {
+            let expected = orbit.state_at(t);
+            for (name, state) in [("elements", elements.state_at(t)), ("to_orbit", elements.to_orbit::<f64>().state_at(t)), ("modified", ModifiedEquinoctialElements::from(elements).state_at(t))] {
+                let err = state_gap(state, expected);
+                assert!(err < 1e-9, "e = {}, t = {}, {}: {:e}", orbit.e, t, name, err);
+            }
+        }
+    }
+}
+
+#[test]
+fn every_way_in_same_elements() {
+    for orbit in orbits().into_iter().filter(|orbit| orbit.e < 1.0) {
+        let elements = EquinoctialElements::from_orbit(&orbit, 1000.0).unwrap();
+        let (pos, vel) = orbit.state_at(1000.0);
+        let from_state = EquinoctialElements::from_state(pos, vel, MU, 1000.0).unwrap();
+        let converted = EquinoctialElements::try_from(ModifiedEquinoctialElements::from_orbit(&orbit, 1000.0).unwrap()).unwrap();
+        for (name, other) in [("from_state", from_state), ("modified", converted)] {
+            let scale = [orbit.a, 1.0, 1.0, 1.0, 1.0, PI];
+            let a = [elements.a, elements.h, elements.k, elements.p, elements.q, elements.lambda];
+            let b = [other.a, other.h, other.k, other.p, other.q, other.lambda];
+            for j in 0..6 {
+                let err = (a[j] - b[j]).abs()/scale[j];
+                assert!(err < 1e-9, "e = {}, {}, element {}: {:e}", orbit.e, name, j, err);
+            }
+        }
+    }
+}
+
+#[test]
+fn open_orbits_not_elliptic() {
+    for orbit in orbits().into_iter().filter(|orbit| orbit.e >= 1.0) {
+        let modified = ModifiedEquinoctialElements::from_orbit(&orbit, 1000.0).unwrap();
+        assert_eq!(EquinoctialElements::from_orbit(&orbit, 0.0), Err(EquinoctialError::NotElliptic), "e = {}", orbit.e);
+        assert_eq!(EquinoctialElements::try_from(modified), Err(EquinoctialError::NotElliptic), "e = {}", orbit.e);
+    }
+}
+
+#[test]
+fn nearly_circular_and_equatorial() {
+    // a hair off, the elements barely move and it flies like the classical orbit
+    let orbit = orbits()[0];
+    let circular = EquinoctialElements::from_orbit(&orbit, 0.0).unwrap();
+    let (pos, vel) = orbit.state_at(0.0);
+    let vel = vector::add(vel, (0.0, 1e-6, 1e-6));
+    let nudged = EquinoctialElements::from_state(pos, vel, MU, 0.0).unwrap();
+    let nudged_modified = ModifiedEquinoctialElements::from_state(pos, vel, MU, 0.0).unwrap();
+    let classical = Orbit::<f64>::from_state(pos, vel, MU, 0.0);
+    assert!(nudged.h.abs().max(nudged.k.abs()).max(nudged.p.abs()).max(nudged.q.abs()) < 1e-9, "{:?}", nudged);
+    assert!((nudged.lambda - circular.lambda).abs() < 1e-9, "lambda {} against {}", nudged.lambda, circular.lambda);
+    for t in [0.0, 1e4, 1e6] {
+        let expected = classical.state_at(t);
+        let (err, modified_err) = (state_gap(nudged.state_at(t), expected), state_gap(nudged_modified.state_at(t), expected));
+        assert!(err < 1e-9 && modified_err < 1e-9, "t = {}: {:e}, modified {:e}", t, err, modified_err);
+        let apart = vector::norm(vector::sub(nudged.state_at(t).0, circular.state_at(t).0));
+        assert!(apart < 10.0, "t = {}: {} m from circular", t, apart);
+    }
+}
+
+#[test]
+fn retrograde_is_error() {
+    let retrograde = Orbit::new(0.1, 7.0e6, PI, 0.0, 0.0, 0.0, MU);
+    assert_eq!(ModifiedEquinoctialElements::from_orbit(&retrograde, 0.0), Err(EquinoctialError::Retrograde));
+}
+
+#[test]
+fn try_state_close_to_parabolic() {
+    // right next to periapsis f' is almost 0, F still has to stop
+    for e in [0.9, 0.99, 0.9999] {
+        for j in 0..24 {
+            let orbit = Orbit::new(e, 7.0e6, 0.4, 0.25*j as f64, 1.0, 100.0, MU);
+            let elements = EquinoctialElements::from_orbit(&orbit, 0.0).unwrap();
+            for dt in [-100.0, -1e-3, -1e-6, 0.0, 1e-6, 1e-3, 100.0, 1e4] {
+                let t = orbit.t0 + dt;
+                let state = elements.try_state_at(t).unwrap_or_else(|err| panic!("e = {}, o = {}, t = {}: {}", e, orbit.o, t, err));
+                assert_eq!(state, elements.state_at(t), "e = {}, o = {}, t = {}", e, orbit.o, t);
+            }
+        }
+    }
+}
+
+#[test]
+fn nan_is_error() {
+    let elements = EquinoctialElements::from_orbit(&orbits()[3], 0.0).unwrap();
+    let err = EquinoctialElements{ lambda: f64::NAN, ..elements }.try_state().unwrap_err();
+    assert_eq!((err.iter, err.e), (50, elements.h.hypot(elements.k)));
+    assert!(err.residual.is_nan());
+}
@@ -0,0 +1,54 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use kepler::*;
+use kepler::angle::{Angle, r32, r64};
+use common::*;
+
+/* fixed point angles
+ * an Orbit<r32> or Orbit<r64> is the Orbit<f64> with the same angles, only the
+ * trig is done in fixed point. Given the exact same angles r64 agrees to the
+ * rounding. r32 also rounds the angle inside each of the three rotations to its
+ * last bit, half of 2pi/2^32, and from the same radians the elements themselves.
+ */
+const ECCENTRICITIES: [f64; 5] = [0.0, 0.3, 0.9, 1.0, 1.5];
+const TIMES: [f64; 5] = [-3000.0, 0.0, 100.0, 555.0, 4000.0];
+
+fn fixed<A: Angle>(e: f64) -> Orbit<A> {
+    return Orbit::new(e, PERIAPSIS, A::from_radians(0.3), A::from_radians(1.0), A::from_radians(2.0), 0.0, MU);
+}
+// the f64 orbit with exactly the angles the fixed point one ended up with
+fn float<A: Angle>(orbit: &Orbit<A>) -> Orbit {
+    return Orbit::new(orbit.e, orbit.q, orbit.i.to_radians(), orbit.o.to_radians(), orbit.w.to_radians(), orbit.t0, orbit.mu);
+}
+fn agrees<A: Angle>(name: &str, tolerance: f64, expected: impl Fn(f64) -> Orbit) {
+    for e in ECCENTRICITIES {
+        let (orbit, expected) = (fixed::<A>(e), expected(e));
+        for t in TIMES {
+            let (pos, vel) = (gap(orbit.pos_at(t), expected.pos_at(t)), gap(orbit.vel_at(t), expected.vel_at(t)));
+            assert!(pos < tolerance && vel < tolerance, "{}, e = {}, t = {}: position {:e}, velocity {:e}", name, e, t, pos, vel);
+        }
+    }
+}
+
+#[test]
+fn r64_same_angles() {
+    agrees::<r64>("r64", 1e-14, |e| float(&fixed::<r64>(e)));
+}
+
+#[test]
+fn r32_same_angles() {
+    agrees::<r32>("r32", 5e-9, |e| float(&fixed::<r32>(e)));
+}
+
+#[test]
+fn r64_from_radians() {
+    agrees::<r64>("r64", 1e-14, leo);
+}
+
+#[test]
+fn r32_from_radians() {
+    agrees::<r32>("r32", 1e-8, leo);
+}
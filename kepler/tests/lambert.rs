@@ -0,0 +1,112 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+use std::f64::consts::PI;
+use kepler::*;
+use kepler::lambert::{lambert, Direction, LambertArc, LambertError};
+
+/* Lambert solver
+ * Vallado's example 7-5 for the numbers, then every solution over a spread of
+ * geometries, times and both directions has to get from r1 to r2 when its orbit
+ * is propagated, with v2 matching on arrival.
+ */
+const MU: f64 = 3.986004418e14;
+const R1: Pos = (7.0e6, 1.0e5, 2.0e5);
+const T1: f64 = 250.0;
+
+// every geometry, time of flight and direction, with up to 5 revolutions
+fn cases() -> Vec<(Pos, f64, Direction)> {
+    let mut cases = Vec::new();
+    for angle in [0.3_f64, 1.5, 2.9, 3.5, 5.0] {
+        for tof in [1.0e3, 5.0e3, 2.0e4, 1.0e5] {
+            for direction in [Direction::Prograde, Direction::Retrograde] {
+                cases.push(((1.2e7*angle.cos(), 1.2e7*angle.sin(), -3.0e5), tof, direction));
+            }
+        }
+    }
+    return cases;
+}
+
+#[test]
+fn vallado_example() {
+    let arcs = lambert::<f64>((15945.34e3, 0.0, 0.0), (12214.83899e3, 10249.46731e3, 0.0), 0.0, 76.0*60.0, MU, Direction::Prograde, 0).unwrap();
+    let (v1, v2) = (arcs[0].v1, arcs[0].v2);
+    assert!(vector::norm(vector::sub(v1, (2058.913, 2915.965, 0.0))) < 0.01, "v1 {:?}", v1);
+    assert!(vector::norm(vector::sub(v2, (-3451.565, 910.315, 0.0))) < 0.01, "v2 {:?}", v2);
+}
+
+#[test]
+fn arcs_reach_r2() {
+    for (r2, tof, direction) in cases() {
+        for arc in lambert::<f64>(R1, r2, T1, tof, MU, direction, 5).unwrap() {
+            let (pos, vel) = arc.orbit.state_at(T1 + tof);
+            let pos_err = vector::norm(vector::sub(pos, r2))/vector::norm(r2);
+            let vel_err = vector::norm(vector::sub(vel, arc.v2))/vector::norm(arc.v2);
+            let start = vector::norm(vector::sub(arc.orbit.pos_at(T1), R1));
+            assert!(pos_err < 1e-9 && vel_err < 1e-9, "{:?} {:?} tof {} revs {}: r2 {:e}, v2 {:e}", r2, direction, tof, arc.revs, pos_err, vel_err);
+            assert!(start < 1e-3, "{:?} {:?} tof {} revs {}: starts {:e} m from r1", r2, direction, tof, arc.revs, start);
+        }
+    }
+}
+
+#[test]
+fn arcs_go_the_right_way() {
+    for (r2, tof, direction) in cases() {
+        for arc in lambert::<f64>(R1, r2, T1, tof, MU, direction, 5).unwrap() {
+            let h = vector::cross(R1, arc.v1).2;
+            assert_eq!(h > 0.0, direction == Direction::Prograde, "{:?} {:?} tof {} revs {}", r2, direction, tof, arc.revs);
+        }
+    }
+}
+
+#[test]
+fn revolutions_fit_the_time() {
+    let mut multi = 0;
+    for (r2, tof, direction) in cases() {
+        for arc in lambert::<f64>(R1, r2, T1, tof, MU, direction, 5).unwrap().iter().filter(|arc| arc.revs > 0) {
+            multi += 1;
+            let period = 2.0*PI/arc.orbit.mean_motion();
+            assert!(period*arc.revs as f64 <= tof && tof < period*(arc.revs + 1) as f64, "{:?} {:?} tof {} revs {}: period {}", r2, direction, tof, arc.revs, period);
+        }
+    }
+    assert!(multi > 0);
+}
+
+#[test]
+fn errors() {
+    assert_eq!(lambert::<f64>((7.0e6, 0.0, 0.0), (1.0e7, 0.0, 0.0), 0.0, 1.0e3, MU, Direction::Prograde, 0), Err(LambertError::Collinear));
+    assert_eq!(lambert::<f64>((7.0e6, 0.0, 0.0), (0.0, 1.0e7, 0.0), 0.0, -1.0, MU, Direction::Prograde, 0), Err(LambertError::TimeOfFlight));
+}
+
+// the single revolution arc, then a left and right for 1, 2... revolutions
+fn check_pairs(arcs: &[LambertArc]) {
+    assert!(arcs.len() % 2 == 1, "{} arcs", arcs.len());
+    assert_eq!((arcs[0].revs, arcs[0].left), (0, false));
+    for (j, pair) in arcs[1..].chunks(2).enumerate() {
+        assert_eq!((pair[0].revs, pair[0].left, pair[1].revs, pair[1].left), (j as u32 + 1, true, j as u32 + 1, false));
+    }
+}
+
+#[test]
+fn too_many_revolutions() {
+    // asking for far more revolutions than fit gives as many pairs as there's time for
+    let arcs = lambert::<f64>((7.0e6, 0.0, 0.0), (0.0, 1.2e7, 1.0e5), 0.0, 2.0e5, MU, Direction::Prograde, 1000).unwrap();
+    assert!(arcs.len() > 3, "{} arcs", arcs.len());
+    check_pairs(&arcs);
+}
+
+#[test]
+fn revolutions_come_in_pairs() {
+    for j in 0..36 {
+        let theta = (j as f64 + 0.5)*PI/18.0;
+        for ratio in [0.5, 1.7, 8.0] {
+            let r2 = (7.0e6*ratio*theta.cos(), 7.0e6*ratio*theta.sin(), 1.0e4);
+            for k in 0..20 {
+                let tof = 2.0e3*1.5_f64.powi(k);
+                if let Ok(arcs) = lambert::<f64>((7.0e6, 0.0, 0.0), r2, 0.0, tof, MU, Direction::Prograde, 1000) {
+                    check_pairs(&arcs);
+                }
+            }
+        }
+    }
+}
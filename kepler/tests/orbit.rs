@@ -0,0 +1,84 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use std::f64::consts::PI;
+use kepler::*;
+use common::*;
+
+/* positions in time
+ * pos_at has to be pos at M = n(t - t0), a whole period later is the same place
+ * and hyperbolic orbits don't wrap.
+ */
+#[test]
+fn pos_at_is_pos_at_mean_anomaly() {
+    for e in [0.0, 0.3, 0.9, 1.0, 1.5] {
+        let orbit = Orbit{ t0: 100.0, ..leo(e) };
+        let n = orbit.mean_motion();
+        for t in [-5000.0, 100.0, 700.0, 1.0e4] {
+            let diff = vector::norm(vector::sub(orbit.pos_at(t), orbit.pos(n*(t - 100.0))));
+            assert!(diff < 1e-6*orbit.q, "e = {}, t = {}: {} m off", e, t, diff);
+        }
+    }
+}
+
+#[test]
+fn elliptic_mean_motion() {
+    for e in [0.0, 0.3, 0.9] {
+        let orbit = leo(e);
+        assert!((orbit.mean_motion() - (MU/orbit.a.powi(3)).sqrt()).abs() < 1e-15, "e = {}", e);
+    }
+}
+
+#[test]
+fn elliptic_repeats_every_period() {
+    for e in [0.0, 0.3, 0.9] {
+        let orbit = Orbit{ t0: 100.0, ..leo(e) };
+        let period = 2.0*PI/orbit.mean_motion();
+        let diff = vector::norm(vector::sub(orbit.pos_at(1234.0 + 3.0*period), orbit.pos_at(1234.0)));
+        assert!(diff < 1e-3, "e = {}: {} m off", e, diff);
+    }
+}
+
+#[test]
+fn open_orbits_dont_wrap() {
+    for e in [1.0, 1.5] {
+        let orbit = Orbit{ t0: 100.0, ..leo(e) };
+        assert!(vector::norm(orbit.pos_at(1.0e6)) > 10.0*vector::norm(orbit.pos_at(1.0e4)), "e = {}", e);
+    }
+}
+
+#[test]
+fn periapsis_at_t0() {
+    let diff = vector::norm(vector::sub(Orbit{ t0: 100.0, ..leo(0.5) }.pos_at(100.0), leo(0.5).pos(0.0)));
+    assert!(diff < 1e-6, "{} m off", diff);
+}
+
+/* velocity
+ * against a central difference of pos_at, and state has to be the two together.
+ */
+#[test]
+fn velocity_is_derivative_of_position() {
+    const DT: f64 = 1e-3;
+    for e in [0.0, 0.3, 0.9, 1.0, 1.5, 4.0] {
+        let orbit = Orbit{ t0: 100.0, ..leo(e) };
+        for t in [-3000.0, 0.0, 100.0, 555.0, 4000.0] {
+            let numeric = vector::scale(vector::sub(orbit.pos_at(t + DT), orbit.pos_at(t - DT)), 0.5/DT);
+            let err = gap(numeric, orbit.vel_at(t));
+            assert!(err < 1e-6, "e = {}, t = {}: {:e}", e, t, err);
+        }
+    }
+}
+
+#[test]
+fn state_is_pos_and_vel() {
+    for e in [0.0, 0.3, 0.9, 1.0, 1.5, 4.0] {
+        let orbit = Orbit{ t0: 100.0, ..leo(e) };
+        for t in [-3000.0, 0.0, 100.0, 555.0, 4000.0] {
+            assert_eq!(orbit.state_at(t), (orbit.pos_at(t), orbit.vel_at(t)), "e = {}, t = {}", e, t);
+            let M = orbit.mean_anomaly(t);
+            assert_eq!(orbit.state(M), (orbit.pos(M), orbit.vel(M)), "e = {}, M = {}", e, M);
+        }
+    }
+}
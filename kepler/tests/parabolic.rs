@@ -0,0 +1,62 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::*;
+use kepler::NEAR_PARABOLIC;
+
+/* parabolic continuity
+ * orbits with the same periapsis either side of e = 1 should close in on the
+ * parabolic one as e does, at the same rate on both sides. Within NEAR_PARABOLIC
+ * Classic goes universal, E/H would lose more to cancellation in M than the orbits
+ * actually differ by.
+ */
+fn closes_in(e: f64) {
+    let parabola = leo(1.0);
+    let orbit = leo(e);
+    for i in -100..=100 {
+        let t = 100.0*i as f64;
+        let err = gap(orbit.pos_at(t), parabola.pos_at(t));
+        assert!(err < 2.0*(e - 1.0).abs(), "e = {}, t = {}: {:e}", e, t, err);
+    }
+}
+
+#[test]
+fn elliptic_side() {
+    for delta in [1e-2, 1e-3, 1e-4, 1e-6, 1e-8, 1e-10] {
+        closes_in(1.0 - delta);
+    }
+}
+
+#[test]
+fn hyperbolic_side() {
+    for delta in [1e-2, 1e-3, 1e-4, 1e-6, 1e-8, 1e-10] {
+        closes_in(1.0 + delta);
+    }
+}
+
+#[test]
+fn smooth_across_the_band_edge() {
+    // just inside goes universal, just outside stays E/H, they should agree
+    for side in [-1.0, 1.0] {
+        let inside = leo(1.0 + side*NEAR_PARABOLIC*(1.0 - 1e-9));
+        let outside = leo(1.0 + side*NEAR_PARABOLIC*(1.0 + 1e-9));
+        for i in -100..=100 {
+            let t = 100.0*i as f64;
+            let err = gap(inside.pos_at(t), outside.pos_at(t));
+            assert!(err < 1e-9, "side {}, t = {}: {:e}", side, t, err);
+        }
+    }
+}
+
+#[test]
+fn try_pos_inside_the_band() {
+    for e in [1.0 - 1e-10, 1.0 + 1e-10] {
+        let orbit = leo(e);
+        for i in -100..=100 {
+            let t = 100.0*i as f64;
+            assert_eq!(orbit.try_pos_at(t), Ok(orbit.pos_at(t)), "e = {}, t = {}", e, t);
+        }
+    }
+}
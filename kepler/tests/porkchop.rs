@@ -0,0 +1,58 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+use kepler::*;
+use kepler::lambert::Direction;
+use kepler::porkchop::{porkchop, Porkchop, PorkchopError};
+
+/* porkchop plot
+ * earth to mars over a whole synodic period so a window has to be in there. the
+ * best C3 should be in the usual 8-30 km^2/s^2, and both image formats get drawn.
+ */
+const SUN: f64 = 1.32712440018e20;
+const DAY: f64 = 86400.0;
+
+fn earth() -> Orbit {
+    return Orbit::new(0.0167, 1.471e11, 0.0, 0.0, 1.796, 0.0, SUN);
+}
+fn mars() -> Orbit {
+    return Orbit::new(0.0934, 2.066e11, 0.0323, 0.865, 5.0, 3.0e7, SUN);
+}
+fn departures() -> Vec<f64> {
+    return (0..120).map(|j| 7.0*DAY*j as f64).collect();
+}
+fn tofs() -> Vec<f64> {
+    return (0..60).map(|j| 100.0*DAY + 5.0*DAY*j as f64).collect();
+}
+fn plot() -> Porkchop {
+    return porkchop(&earth(), &mars(), &departures(), &tofs(), Direction::Prograde);
+}
+
+#[test]
+fn best_c3_in_range() {
+    let (t, tof, c3) = plot().min_c3().unwrap();
+    assert!(c3 > 8.0e6 && c3 < 30.0e6, "{:.2} km^2/s^2 leaving day {:.0}, {:.0} days", c3/1e6, t/DAY, tof/DAY);
+}
+
+#[test]
+fn renders_png_and_svg() {
+    let plot = plot();
+    for name in ["porkchop.png", "porkchop.svg"] {
+        let path = std::env::temp_dir().join(name);
+        plot.render(&path, (800, 600), &[10.0, 15.0, 20.0, 30.0, 50.0], &[3.0, 4.0, 5.0, 7.0]).unwrap();
+        let size = std::fs::metadata(&path).unwrap().len();
+        assert!(size > 1000, "{}: {} bytes", path.display(), size);
+    }
+}
+
+#[test]
+fn empty_axis_is_error() {
+    // nothing to draw across is an error, not a panic
+    let (departures, tofs) = (departures(), tofs());
+    let path = std::env::temp_dir().join("porkchop_empty.png");
+    for (departures, tofs) in [(&departures[..0], &tofs[..]), (&departures[..], &tofs[..0]), (&departures[..1], &tofs[..]), (&departures[..], &[tofs[0], tofs[0]][..])] {
+        let plot = porkchop(&earth(), &mars(), departures, tofs, Direction::Prograde);
+        let err = plot.render(&path, (800, 600), &[10.0], &[3.0]).unwrap_err();
+        assert_eq!(err.downcast_ref::<PorkchopError>(), Some(&PorkchopError::Axis), "{} departures, {} tofs", departures.len(), tofs.len());
+    }
+}
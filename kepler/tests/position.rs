@@ -0,0 +1,91 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use kepler::position::{Position, Displacement};
+use common::*;
+
+/* fixed point positions
+ * the arithmetic between points and displacements, and millimetres surviving
+ * far from the origin where f64 metres wouldn't.
+ */
+#[test]
+fn points_and_displacements() {
+    let a = Position::new(1, 2, 3);
+    let b = Position::new(-10, 20, 5);
+    let d = b - a;
+    assert_eq!(d, Displacement::new(-11, 18, 2));
+    assert_eq!(a + d, b);
+    assert_eq!(b - d, a);
+    assert_eq!(d + d, d*2);
+    assert_eq!(d - d, Displacement::ZERO);
+    assert_eq!(-d, a - b);
+}
+
+#[test]
+fn assign_ops() {
+    let (a, b) = (Position::new(1, 2, 3), Position::new(-10, 20, 5));
+    let d = b - a;
+    let mut c = a;
+    c += d;
+    assert_eq!(c, b);
+    c -= d;
+    assert_eq!(c, a);
+    let mut e = d;
+    e += d;
+    e -= d*3;
+    assert_eq!(e, -d);
+}
+
+#[test]
+fn metres_in_and_out() {
+    assert_eq!(Position::from_f64((1.0, -2.5, 0.0004)), Position::new(1000, -2500, 0));
+    assert_eq!(Displacement::new(1500, 0, -250).to_f64(), (1.5, 0.0, -0.25));
+}
+
+#[test]
+fn length() {
+    assert_eq!(Displacement::new(3000, 4000, 0).length(), 5.0);
+    let edge = Displacement::new(i64::MAX, i64::MAX, i64::MAX).length();
+    assert!((edge/(3.0_f64.sqrt()*i64::MAX as f64/1000.0) - 1.0).abs() < 1e-15, "{:e}", edge);
+    assert_eq!(Displacement::new(i64::MIN, 0, 0).length(), 2.0_f64.powi(63)/1000.0);
+}
+
+#[test]
+fn millimetres_far_out() {
+    // 30000 AU out a millimetre step is still a millimetre
+    let far = Position::from_f64((4.5e15, -4.5e15, 1.0e15));
+    let moved = far + Displacement::new(1, 0, -1);
+    assert_eq!(moved - far, Displacement::new(1, 0, -1));
+    assert_eq!(Position::ORIGIN + (far - Position::ORIGIN), far);
+    let orbit = leo(0.1);
+    assert_eq!(orbit.pos_around(far, 1.0) - far, orbit.pos_fixed(1.0));
+}
+
+#[test]
+fn opposite_sides_within_range() {
+    // 30000 AU either side is as far apart as two points can be
+    let (a, b) = (Position::from_f64((4.5e15, 0.0, -4.5e15)), Position::from_f64((-4.5e15, 0.0, 4.5e15)));
+    assert_eq!((a - b).to_f64(), (9.0e15, 0.0, -9.0e15));
+    assert_eq!(b + (a - b), a);
+}
+
+#[test]
+#[should_panic(expected = "position out of range")]
+fn opposite_sides_past_range() {
+    let (a, b) = (Position::new(i64::MAX/4*3, 0, 0), Position::new(-i64::MAX/4*3, 0, 0));
+    let _ = a - b;
+}
+
+#[test]
+#[should_panic(expected = "position out of range")]
+fn stepping_off_the_edge() {
+    let _ = Position::new(i64::MAX, 0, 0) + Displacement::new(1, 0, 0);
+}
+
+#[test]
+#[should_panic(expected = "position out of range")]
+fn scaling_off_the_edge() {
+    let _ = Displacement::new(0, i64::MAX/2 + 1, 0)*2;
+}
@@ -0,0 +1,94 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+use kepler::*;
+use kepler::body::{System, BodyId};
+use kepler::soi::Transition;
+
+/* SOI transitions
+ * a ship from low earth orbit out to the moon, around it and back out, then one
+ * leaving earth for the sun. at every crossing the ship has to be on the SOI, in
+ * the same place and moving the same way on both sides, and nowhere near it before.
+ */
+const SUN: f64 = 1.32712440018e20;
+const EARTH: f64 = 3.986004418e14;
+const MOON: f64 = 4.9048695e12;
+
+fn system() -> (System, BodyId, BodyId) {
+    let mut system = System::new("sun", SUN, 6.957e8);
+    let earth = system.add(system.root(), "earth", EARTH, 6.371e6, Orbit::new(0.0167, 1.471e11, 0.0, 0.0, 1.796, 0.0, SUN)).unwrap();
+    let moon = system.add(earth, "moon", MOON, 1.737e6, Orbit::new(0.0, 3.844e8, 0.0, 0.0, 0.0, 0.0, EARTH)).unwrap();
+    return (system, earth, moon);
+}
+// apoapsis just past the moon's orbit, timed so the moon is there
+fn ship() -> Orbit {
+    return Orbit::new(0.966, 6.7e6, 0.0, 0.0, 4.29, 0.0, EARTH);
+}
+fn arrival(system: &System, earth: BodyId, moon: BodyId) -> Transition {
+    let (id, arrive) = system.next_transition(earth, &ship(), 0.0, 1.0e6).unwrap();
+    assert_eq!(id, moon);
+    return arrive;
+}
+
+#[test]
+fn arrives_on_the_moons_soi() {
+    let (system, earth, moon) = system();
+    let arrive = arrival(&system, earth, moon);
+    let moon_orbit = system[moon].orbit.unwrap();
+    let gap = vector::norm(vector::sub(ship().pos_at(arrive.t), moon_orbit.pos_at(arrive.t))) - system[moon].soi;
+    assert!(gap.abs() <= 1e-3, "t = {}: {:e} m off the SOI", arrive.t, gap);
+}
+
+#[test]
+fn arrival_keeps_the_state() {
+    let (system, earth, moon) = system();
+    let arrive = arrival(&system, earth, moon);
+    let (ship, moon_orbit, t) = (ship(), system[moon].orbit.unwrap(), arrive.t);
+    let pos_err = vector::norm(vector::sub(vector::add(arrive.orbit.pos_at(t), moon_orbit.pos_at(t)), ship.pos_at(t)));
+    let vel_err = vector::norm(vector::sub(vector::add(arrive.orbit.vel_at(t), moon_orbit.vel_at(t)), ship.vel_at(t)));
+    assert!(pos_err < 1e-3, "{:e} m", pos_err);
+    assert!(vel_err < 1e-6, "{:e} m/s", vel_err);
+}
+
+#[test]
+fn nowhere_near_the_moon_before() {
+    let (system, earth, moon) = system();
+    let arrive = arrival(&system, earth, moon);
+    let (ship, moon_orbit) = (ship(), system[moon].orbit.unwrap());
+    for j in 0..1000 {
+        let t = arrive.t*j as f64/1000.0;
+        assert!(vector::norm(vector::sub(ship.pos_at(t), moon_orbit.pos_at(t))) > system[moon].soi, "t = {}", t);
+    }
+}
+
+#[test]
+fn leaves_the_moon_for_earth() {
+    let (system, earth, moon) = system();
+    let arrive = arrival(&system, earth, moon);
+    let (id, leave) = system.next_transition(moon, &arrive.orbit, arrive.t, arrive.t + 1.0e6).unwrap();
+    assert_eq!(id, earth);
+    assert!(leave.t > arrive.t);
+    let moon_orbit = system[moon].orbit.unwrap();
+    let r = vector::norm(arrive.orbit.pos_at(leave.t));
+    let pos_err = vector::norm(vector::sub(leave.orbit.pos_at(leave.t), vector::add(arrive.orbit.pos_at(leave.t), moon_orbit.pos_at(leave.t))));
+    assert!((r - system[moon].soi).abs() < 1e-3, "{:e} m off the SOI", r - system[moon].soi);
+    assert!(pos_err < 1e-3, "{:e} m", pos_err);
+
+    // doesn't go straight back into the moon it just left
+    if let Some((_, next)) = system.next_transition(earth, &leave.orbit, leave.t, leave.t + 1.0e5) {
+        assert!(next.t > leave.t + 1.0, "back in at t = {}", next.t);
+    }
+}
+
+#[test]
+fn escapes_earth_for_the_sun() {
+    let (system, earth, _) = system();
+    let escape = Orbit::new(1.2, 6.7e6, 0.5, 1.0, 0.0, 0.0, EARTH);
+    let (id, helio) = system.next_transition(earth, &escape, 0.0, 1.0e8).unwrap();
+    assert_eq!(id, system.root());
+    let earth_orbit = system[earth].orbit.unwrap();
+    let r = vector::norm(escape.pos_at(helio.t));
+    let pos_err = vector::norm(vector::sub(helio.orbit.pos_at(helio.t), vector::add(escape.pos_at(helio.t), earth_orbit.pos_at(helio.t))));
+    assert!((r - system[earth].soi).abs() < 1e-3, "{:e} m off the SOI", r - system[earth].soi);
+    assert!(pos_err < 1e-2, "{:e} m", pos_err);
+}
@@ -0,0 +1,186 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use std::{sync::Arc, f64::consts::PI};
+use kepler::*;
+use kepler::solver::{Solver, Newton, Danby, KeplerSolver};
+use kepler::solver::table::KeplerTable;
+use common::*;
+
+/* lookup table solver
+ * checked against Danby, which builds the tables. Newton itself is off by ~1e-9
+ * near e = 1, M = 0, see the bench binary, which is also where they get timed.
+ * Solver::Table only uses the shared table while it's held, and tests run side by
+ * side, so every test holding one has an e no other test in here uses.
+ */
+#[test]
+fn table_matches_danby() {
+    const COUNT: u32 = 100000;
+    for e in [0.05, 0.35, 0.75, 0.95, 0.985] {
+        let _held = KeplerTable::shared(e);
+        let table = Orbit{ solver: Solver::Table, ..leo(e) };
+        let danby = Orbit{ solver: Solver::Danby, ..leo(e) };
+        for j in 0..COUNT {
+            let M = 2.0*PI*(j as f64)/COUNT as f64 - PI;
+            let err = gap(table.pos(M), danby.pos(M));
+            assert!(err < 1e-13, "e = {}, M = {}: {:e}", e, M, err);
+        }
+    }
+}
+
+/* checked positions
+ * valid input is Ok over several revolutions either way with every solver and
+ * agrees with pos, a hyperbolic M too big for sinh is an error.
+ */
+#[test]
+fn try_pos_every_solver() {
+    for solver in Solver::ALL {
+        for e in [0.0, 0.1, 0.5, 0.9] {
+            let orbit = Orbit{ solver, ..leo(e) };
+            for j in 0..=4000 {
+                let M = -20.0 + 40.0*j as f64/4000.0;
+                let pos = orbit.try_pos(M).unwrap_or_else(|err| panic!("{:?}: {}", solver, err));
+                assert_eq!(pos, orbit.pos(M), "{:?}, e = {}, M = {}", solver, e, M);
+            }
+            assert!(orbit.try_pos_at(1.0e7).is_ok(), "{:?}, e = {}", solver, e);
+        }
+    }
+}
+
+#[test]
+fn try_pos_close_to_parabolic() {
+    // right up to e = 1 without running out the iterations, 0.9999 is past NEAR_PARABOLIC
+    for e in [0.99, 0.9999] {
+        let orbit = leo(e);
+        for j in 0..=4000 {
+            let M = -20.0 + 40.0*j as f64/4000.0;
+            assert!(orbit.try_pos(M).is_ok(), "e = {}, M = {}", e, M);
+        }
+    }
+}
+
+#[test]
+fn try_pos_at_close_to_parabolic() {
+    for e in [0.99, 0.9999] {
+        let orbit = Orbit{ solver: Solver::Danby, ..leo(e) };
+        for i in -1000..=1000 {
+            let t = 10.0*i as f64;
+            assert!(orbit.try_pos_at(t).is_ok(), "e = {}, t = {}", e, t);
+        }
+    }
+}
+
+#[test]
+fn small_M_close_to_parabolic() {
+    // f' = 1 - e*cos(E) is almost 0 so the steps only get down to rounding over f'.
+    // Markley doesn't iterate
+    for solver in [Solver::Newton, Solver::Halley, Solver::Danby, Solver::Fukushima, Solver::Table] {
+        for e in [0.9999, 1.0 - 1e-8] {
+            for j in -1000..=1000 {
+                let M = 1e-6*j as f64/1000.0;
+                let solution = solver.solve(e, M);
+                let E = Solver::Markley.solve(e, M).E;
+                assert!(solution.converged, "{:?}, e = {}, M = {:e}", solver, e, M);
+                assert!((solution.E - E).abs() <= 1e-9*E.abs(), "{:?}, e = {}, M = {:e}: {} vs {}", solver, e, M, solution.E, E);
+            }
+        }
+    }
+}
+
+#[test]
+fn newton_tiny_E() {
+    // E - e*sin(E) is all cancellation here, worked out again from
+    // (1-e)*E + e*(E - sin(E)) = M with the series for E - sin(E)
+    let (e, M): (f64, f64) = (1.0 - 1e-10, 5.4e-15);
+    let mut E = (6.0*M).cbrt();
+    for _ in 0..20 {
+        let f = (1.0 - e)*E + e*(E.powi(3)/6.0 - E.powi(5)/120.0) - M;
+        let f1 = (1.0 - e) + e*(E.powi(2)/2.0 - E.powi(4)/24.0);
+        E -= f/f1;
+    }
+    let solution = Newton.solve(e, M);
+    assert!(solution.converged);
+    assert!(((solution.E - E)/E).abs() < 1e-6, "{} vs {}", solution.E, E);
+}
+
+#[test]
+fn newton_reduces_M() {
+    let iter = Newton.solve(0.5, 9.0).iter;
+    assert!(iter < 10, "{} iterations", iter);
+}
+
+#[test]
+fn hyperbolic_overflow_is_error() {
+    let err = leo(2.0).try_pos(f64::MAX).unwrap_err();
+    assert_eq!((err.iter, err.e, err.M), (100, 2.0, f64::MAX));
+}
+
+#[test]
+fn nan_is_error() {
+    assert!(leo(0.5).try_pos(f64::NAN).is_err());
+}
+
+/* lookup tables with every orbit on its own e
+ * nobody holds a table for any of them, so it's Danby bit for bit and costs
+ * what Danby does. Holding one switches that e over to it on every thread, and
+ * back once it's dropped. A table built and kept by hand works the same.
+ */
+const MANY: usize = 2000;
+
+#[test]
+fn unheld_e_is_danby() {
+    for j in 0..MANY {
+        let e = 0.95*j as f64/MANY as f64 + 1e-7;
+        let M = 2.0*PI*j as f64/MANY as f64 - PI;
+        assert_eq!(Solver::Table.solve(e, M).E.to_bits(), Danby.solve(e, M).E.to_bits(), "e = {}, M = {}", e, M);
+    }
+}
+
+#[test]
+fn held_table_is_used() {
+    let e = 0.45;
+    let table = KeplerTable::shared(e);
+    assert!(Arc::ptr_eq(&table, &KeplerTable::shared(e)));
+    for j in 0..=1000 {
+        let M = -10.0 + 20.0*j as f64/1000.0;
+        assert_eq!(Solver::Table.solve(e, M).E.to_bits(), table.solve(M).E.to_bits(), "M = {}", M);
+    }
+    drop(table);
+    for j in 0..=1000 {
+        let M = -10.0 + 20.0*j as f64/1000.0;
+        assert_eq!(Solver::Table.solve(e, M).E.to_bits(), Danby.solve(e, M).E.to_bits(), "M = {}", M);
+    }
+}
+
+#[test]
+fn same_on_every_thread() {
+    // 0.65 is held the whole time, 0.55 never is
+    let table = KeplerTable::shared(0.65);
+    let threads: Vec<_> = (0..8).map(|_| std::thread::spawn(|| {
+        return (0..=1000).map(|j| {
+            let M = -PI + 2.0*PI*j as f64/1000.0;
+            return (Solver::Table.solve(0.65, M).E, Solver::Table.solve(0.55, M).E);
+        }).collect::<Vec<_>>();
+    })).collect();
+    for thread in threads {
+        for (j, (held, unheld)) in thread.join().unwrap().into_iter().enumerate() {
+            let M = -PI + 2.0*PI*j as f64/1000.0;
+            assert_eq!(held.to_bits(), table.solve(M).E.to_bits(), "M = {}", M);
+            assert_eq!(unheld.to_bits(), Danby.solve(0.55, M).E.to_bits(), "M = {}", M);
+        }
+    }
+}
+
+#[test]
+fn owned_table_matches_danby() {
+    for e in [0.7, 0.9999, 1.0 - 1e-8] {
+        let table = KeplerTable::new(e);
+        for j in 0..=1000 {
+            let M = -10.0 + 20.0*j as f64/1000.0;
+            let err = (table.solve(M).E - Danby.solve(e, M).E).abs();
+            assert!(err < 1e-13, "e = {}, M = {}: {:e}", e, M, err);
+        }
+    }
+}
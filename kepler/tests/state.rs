@@ -0,0 +1,51 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use std::f64::consts::PI;
+use kepler::*;
+use common::*;
+
+/* state vector round trip
+ * orbit -> pos/vel -> orbit -> pos should land back in the same place. Includes
+ * the circular, equatorial and hyperbolic cases where elements go missing.
+ */
+fn round_trip(e: f64) {
+    for i in [0.0, 0.3, PI/2.0, 2.5, PI] {
+        let orbit = Orbit{ i, t0: 100.0, ..leo(e) };
+        for t in [-3000.0, 0.0, 100.0, 555.0, 4000.0] {
+            let (pos, vel) = orbit.state_at(t);
+            let back: Orbit = Orbit::from_state(pos, vel, orbit.mu, t);
+            for dt in [0.0, 250.0] {
+                let err = vector::norm(vector::sub(back.pos_at(t+dt), orbit.pos_at(t+dt)))/vector::norm(pos);
+                assert!(err < 1e-9, "e = {}, i = {}, t = {}, dt = {}: {:e}", e, i, t, dt, err);
+            }
+        }
+    }
+}
+
+#[test]
+fn round_trip_circular() {
+    round_trip(0.0);
+    round_trip(1e-13);
+}
+
+#[test]
+fn round_trip_elliptic() {
+    for e in [0.1, 0.5, 0.9, 0.99] {
+        round_trip(e);
+    }
+}
+
+#[test]
+fn round_trip_parabolic() {
+    round_trip(1.0);
+}
+
+#[test]
+fn round_trip_hyperbolic() {
+    for e in [1.01, 1.5, 4.0] {
+        round_trip(e);
+    }
+}
@@ -0,0 +1,88 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use std::f64::consts::PI;
+use kepler::*;
+use kepler::transfer::{self, Transfer, TransferError};
+use common::*;
+
+/* Hohmann and bi-elliptic transfers
+ * circular to circular against the textbook formulas, then elliptic orbits with
+ * different apsides. every burn has to leave the ship where it was, and the last
+ * one has to put it on the target's path.
+ */
+const R1: f64 = PERIAPSIS;
+const R2: f64 = 42.164e6;
+
+fn circular(r: f64) -> Orbit {
+    return Orbit::new(0.0, r, 0.2, 1.0, 0.0, 0.0, MU);
+}
+fn follows(transfer: &Transfer, from: &Orbit, to: &Orbit) {
+    let mut before = *from;
+    for (j, (burn, after)) in transfer.burns.iter().zip(&transfer.orbits).enumerate() {
+        let moved = vector::norm(vector::sub(after.pos_at(burn.t), before.pos_at(burn.t)));
+        assert!(moved < 1e-3, "burn {}: moved {:e} m", j, moved);
+        let dv = vector::sub(after.vel_at(burn.t), before.vel_at(burn.t));
+        let err = vector::norm(vector::sub(dv, burn.dv));
+        assert!(err < 1e-6, "burn {}: dv off by {:e} m/s", j, err);
+        before = *after;
+    }
+    let total: f64 = transfer.burns.iter().map(|burn| vector::norm(burn.dv)).sum();
+    assert!((total - transfer.total_dv).abs() < 1e-9, "{} m/s in burns, total_dv {}", total, transfer.total_dv);
+    assert!((before.e - to.e).abs() < 1e-9, "ends on e = {}, target {}", before.e, to.e);
+    assert!((before.q - to.q).abs() < 1e-3, "ends on q = {}, target {}", before.q, to.q);
+    if to.e > 0.0 {
+        let diff = vector::norm(vector::sub(before.pos(0.0), to.pos(0.0)));
+        assert!(diff < 1e-2, "periapsis {:e} m apart", diff);
+    }
+}
+
+#[test]
+fn hohmann_circular() {
+    let (leo, geo) = (circular(R1), circular(R2));
+    let hohmann = transfer::hohmann(&leo, &geo, 123.0).unwrap();
+    let dv1 = (MU/R1).sqrt()*((2.0*R2/(R1 + R2)).sqrt() - 1.0);
+    let dv2 = (MU/R2).sqrt()*(1.0 - (2.0*R1/(R1 + R2)).sqrt());
+    let time = PI*((R1 + R2).powi(3)/(8.0*MU)).sqrt();
+    assert!((hohmann.total_dv - (dv1 + dv2)).abs() < 1e-6, "{} m/s, expected {}", hohmann.total_dv, dv1 + dv2);
+    assert!((hohmann.arrival() - 123.0 - time).abs() < 1e-6, "{} s, expected {}", hohmann.arrival() - 123.0, time);
+    assert_eq!(hohmann.burns.len(), 2);
+    follows(&hohmann, &leo, &geo);
+}
+
+#[test]
+fn bi_elliptic_wins_far_out() {
+    // past r2/r1 = 15.58 a far enough bi-elliptic wins
+    let (leo, far) = (circular(R1), circular(20.0*R1));
+    let rb = 100.0*R1;
+    let hohmann = transfer::hohmann(&leo, &far, 0.0).unwrap();
+    let bi = transfer::bi_elliptic(&leo, &far, rb, 0.0).unwrap();
+    let v = |r: f64, a: f64| (MU*(2.0/r - 1.0/a)).sqrt();
+    let expected = (v(R1, (R1 + rb)/2.0) - v(R1, R1))
+        + (v(rb, (rb + 20.0*R1)/2.0) - v(rb, (R1 + rb)/2.0))
+        + (v(20.0*R1, (rb + 20.0*R1)/2.0) - v(20.0*R1, 20.0*R1));
+    assert!((bi.total_dv - expected).abs() < 1e-6, "{} m/s, expected {}", bi.total_dv, expected);
+    assert!(bi.total_dv < hohmann.total_dv, "{} m/s, hohmann {}", bi.total_dv, hohmann.total_dv);
+    assert_eq!(bi.burns.len(), 3);
+    follows(&bi, &leo, &far);
+}
+
+#[test]
+fn elliptic_both_ways() {
+    // apsides pointing different ways
+    let low = Orbit::new(0.1, 7.0e6, 0.2, 1.0, 0.5, 500.0, MU);
+    let high = Orbit::new(0.3, 2.0e7, 0.2, 1.0, 2.0, 0.0, MU);
+    for (from, to) in [(low, high), (high, low)] {
+        follows(&transfer::hohmann(&from, &to, 1000.0).unwrap(), &from, &to);
+        follows(&transfer::bi_elliptic(&from, &to, 1.0e8, 1000.0).unwrap(), &from, &to);
+    }
+}
+
+#[test]
+fn errors() {
+    let tilted = Orbit::new(0.0, R2, 0.3, 1.0, 0.0, 0.0, MU);
+    assert_eq!(transfer::hohmann(&circular(R1), &tilted, 0.0), Err(TransferError::NotCoplanar));
+    assert_eq!(transfer::bi_elliptic(&circular(R1), &circular(R2), R1, 0.0), Err(TransferError::LowApoapsis));
+}
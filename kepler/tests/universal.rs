@@ -0,0 +1,65 @@
+#![allow(non_snake_case)]
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use kepler::*;
+use kepler::universal::{stumpff_c, stumpff_s};
+use common::*;
+
+/* universal variables
+ * agrees with the classic path away from e = 1 and keeps closing in on the
+ * parabolic orbit long after E/H stop being able to.
+ */
+fn universal(e: f64) -> Orbit {
+    return Orbit{ propagator: Propagator::Universal, ..leo(e) };
+}
+
+#[test]
+fn matches_classic() {
+    for e in [0.0, 0.3, 0.9, 1.0, 1.5, 5.0] {
+        let (classic, universal) = (leo(e), universal(e));
+        for i in -100..=100 {
+            let t = 100.0*i as f64;
+            let err = gap(universal.pos_at(t), classic.pos_at(t));
+            assert!(err < 1e-9, "e = {}, t = {}: {:e}", e, t, err);
+        }
+    }
+}
+
+#[test]
+fn closes_in_on_parabola() {
+    let parabola = leo(1.0);
+    for delta in [1e-4, 1e-6, 1e-8, 1e-10] {
+        for e in [1.0-delta, 1.0+delta] {
+            let orbit = universal(e);
+            for i in -100..=100 {
+                let t = 100.0*i as f64;
+                let err = gap(orbit.pos_at(t), parabola.pos_at(t));
+                assert!(err < 2.0*delta, "e = {}, t = {}: {:e}", e, t, err);
+            }
+        }
+    }
+}
+
+#[test]
+fn converges_over_many_revolutions() {
+    // M is taken from the nearest periapsis first, ellipses repeat
+    for e in [0.5, 0.9999, 1.0 - 1e-8, 1.0 + 1e-8, 2.0] {
+        let orbit = universal(e);
+        for j in 0..=4000 {
+            let M = -20.0 + 40.0*j as f64/4000.0;
+            assert!(orbit.try_pos(M).is_ok(), "e = {}, M = {}", e, M);
+        }
+    }
+}
+
+#[test]
+fn stumpff_series_meets_closed_form() {
+    for z in [-1.0, 1.0] {
+        let (inside, outside) = (z*(1.0 - 1e-15), z*(1.0 + 1e-15));
+        let c = (stumpff_c(inside)/stumpff_c(outside) - 1.0).abs();
+        let s = (stumpff_s(inside)/stumpff_s(outside) - 1.0).abs();
+        assert!(c < 2e-15 && s < 2e-15, "z = {}: C {:e}, S {:e}", z, c, s);
+    }
+}